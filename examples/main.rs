@@ -47,8 +47,12 @@ fn main() -> wry::Result<()> {
         *control_flow = ControlFlow::Wait;
 
         if !ASYNC {
-            if let Ok(event) = MenuEvent::receiver().try_recv() {
-                println!("MenuEvent:{:?}", event.item.label);
+            if let Ok(MenuEvent::Selected {
+                item,
+                ..
+            }) = MenuEvent::receiver().try_recv()
+            {
+                println!("MenuEvent:{:?}", item.label);
             }
         }
 
@@ -75,7 +79,7 @@ fn main() -> wry::Result<()> {
                     smol::spawn(async move {
                         let menu = MENU_MAP.try_lock().unwrap();
                         let result = menu.popup_at_async(x, y).await;
-                        if let Some(item) = result {
+                        if let Some(item) = result.into_item() {
                             println!("Async MenuEvent:{:?}", item.label);
                         }
                     })
@@ -102,12 +106,16 @@ fn main() -> wry::Result<()> {
                 let mut menu = MENU_MAP.try_lock().unwrap();
                 let radio = MenuItem::new_radio_item("new_radio", "new_radio_label", "Theme", None, true, false, None);
                 let playback_speed = menu.get_menu_item_by_id("Theme").unwrap();
-                playback_speed.submenu.unwrap().insert(radio, 1);
+                if let Err(e) = playback_speed.submenu.unwrap().insert(radio, 1) {
+                    println!("{}", e);
+                }
 
                 let mut item = MenuItem::new_submenu_item("newsubmenu_id", "label", false, None);
                 item.add_menu_item(MenuItem::new_text_item("id1", "label1", Some("Alt+G"), false, None));
                 item.add_menu_item(MenuItem::new_text_item("id2", "label2", None, false, None));
-                menu.append(item);
+                if let Err(e) = menu.append(item) {
+                    println!("{}", e);
+                }
             }
             Event::UserEvent(UserEvent::ChangeStateAndIcon) => {
                 let menu = MENU_MAP.try_lock().unwrap();
@@ -116,11 +124,11 @@ fn main() -> wry::Result<()> {
                 }
 
                 if let Some(target) = menu.get_menu_item_by_id("fittowindow").as_mut() {
-                    target.set_checked(!target.checked);
+                    target.set_checked(!target.check_state.is_checked());
                 }
 
                 if let Some(target) = menu.get_menu_item_by_id("dark").as_mut() {
-                    target.set_checked(!target.checked);
+                    target.set_checked(!target.check_state.is_checked());
                 }
 
                 if let Some(target) = menu.get_menu_item_by_id("SeekSpeed").as_mut() {
@@ -129,10 +137,10 @@ fn main() -> wry::Result<()> {
 
                 if let Some(target) = menu.get_menu_item_by_id("TogglePlaylistWindow").as_mut() {
                     let icon = target.icon.clone();
-                    target.set_icon(None);
+                    let _ = target.set_icon(None);
 
                     if let Some(target) = menu.get_menu_item_by_id("Capture").as_mut() {
-                        target.set_icon(icon);
+                        let _ = target.set_icon(icon);
                     }
                 }
             }
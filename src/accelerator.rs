@@ -0,0 +1,818 @@
+use crate::{config::AcceleratorFormat, Error};
+#[cfg(feature = "accelerator")]
+use std::time::{Duration, Instant};
+use std::{fmt, str::FromStr};
+
+/// Time to wait for the next key of a chord before it is cancelled.
+#[cfg(feature = "accelerator")]
+pub const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Key names accepted by Accelerator other than A-Z, 0-9 and F1-F24.
+const NAMED_KEYS: [&str; 45] = [
+    "Plus",
+    "Space",
+    "Tab",
+    "CapsLock",
+    "NumLock",
+    "ScrollLock",
+    "Backspace",
+    "Delete",
+    "Insert",
+    "Enter",
+    "ArrowLeft",
+    "ArrowUp",
+    "ArrowRight",
+    "ArrowDown",
+    "End",
+    "Home",
+    "PageUp",
+    "PageDown",
+    "Escape",
+    "BrowserHome",
+    "AudioVolumeMute",
+    "AudioVolumeDown",
+    "AudioVolumeUp",
+    "MediaTrackNext",
+    "MediaTrackPrevious",
+    "MediaStop",
+    "MediaPlayPause",
+    "PrintScreen",
+    "Comma",
+    "Minus",
+    "Period",
+    "Semicolon",
+    "Slash",
+    "Backquote",
+    "BracketLeft",
+    "Backslash",
+    "BracketRight",
+    "Quote",
+    "Pause",
+    "KanaMode",
+    "NonConvert",
+    "Help",
+    "ContextMenu",
+    "Convert",
+    "Equal",
+];
+
+const KEY_ALIASES: [(&str, &str); 25] = [
+    ("+", "Plus"),
+    ("ESC", "Escape"),
+    ("RETURN", "Enter"),
+    ("DEL", "Delete"),
+    ("INS", "Insert"),
+    ("BACK", "Backspace"),
+    ("LEFT", "ArrowLeft"),
+    ("UP", "ArrowUp"),
+    ("RIGHT", "ArrowRight"),
+    ("DOWN", "ArrowDown"),
+    ("PGUP", "PageUp"),
+    ("PGDN", "PageDown"),
+    ("PRINT", "PrintScreen"),
+    ("MENU", "ContextMenu"),
+    (",", "Comma"),
+    ("-", "Minus"),
+    ("=", "Equal"),
+    (".", "Period"),
+    (";", "Semicolon"),
+    ("/", "Slash"),
+    ("`", "Backquote"),
+    ("[", "BracketLeft"),
+    ("\\", "Backslash"),
+    ("]", "BracketRight"),
+    ("'", "Quote"),
+];
+
+/// Modifier keys of Accelerator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Super, Meta, Cmd or Windows logo key.
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    pub fn is_empty(&self) -> bool {
+        !self.ctrl && !self.alt && !self.shift && !self.super_key
+    }
+
+    /// Sets the modifier for the name. Returns None if the name is not a modifier, and Some(false) if the modifier is already set.
+    fn set(&mut self, name: &str) -> Option<bool> {
+        let modifier = match name.to_uppercase().as_str() {
+            "CTRL" | "CONTROL" | "CMDORCTRL" | "CMDORCONTROL" | "COMMANDORCTRL" | "COMMANDORCONTROL" => &mut self.ctrl,
+            "ALT" | "OPTION" => &mut self.alt,
+            "SHIFT" => &mut self.shift,
+            "SUPER" | "META" | "CMD" | "COMMAND" | "WIN" | "WINDOWS" => &mut self.super_key,
+            _ => return None,
+        };
+        let is_new = !*modifier;
+        *modifier = true;
+        Some(is_new)
+    }
+}
+
+/// Parsed accelerator such as "Ctrl+Shift+S".
+///
+/// Modifiers are case-insensitive and each can appear only once. Cmd, Meta and Win are parsed as Super, and CmdOrCtrl as Ctrl.
+/// Keys are A-Z, 0-9, F1-F24 and named keys such as Enter, Escape, ArrowUp or Comma.
+/// Display returns the normalized form like "Ctrl+Alt+Shift+Super+S".
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Accelerator {
+    modifiers: Modifiers,
+    key: String,
+}
+
+impl Accelerator {
+    /// Creates an Accelerator from the modifiers and the key name.
+    pub fn new(modifiers: Modifiers, key: &str) -> Result<Self, Error> {
+        let normalized_key = normalize_key(key).ok_or_else(|| Error::InvalidAccelerator {
+            accelerator: key.to_string(),
+            message: format!("unknown key \"{}\"", key),
+        })?;
+
+        Ok(Self {
+            modifiers,
+            key: normalized_key,
+        })
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    /// Normalized key name such as "S", "F11" or "Enter".
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+}
+
+impl Accelerator {
+    /// Returns the display text in the format. AcceleratorFormat::Native is the same as Text.
+    pub fn format(&self, format: &AcceleratorFormat) -> String {
+        let modifiers = [(self.modifiers.ctrl, "Ctrl", "⌃"), (self.modifiers.alt, "Alt", "⌥"), (self.modifiers.shift, "Shift", "⇧"), (self.modifiers.super_key, "Super", "⌘")];
+        let pressed = modifiers.iter().filter(|(pressed, _, _)| *pressed);
+
+        match format {
+            AcceleratorFormat::Symbols => {
+                let mut text: String = pressed.map(|(_, _, symbol)| *symbol).collect();
+                text.push_str(lookup(&KEY_SYMBOLS, &self.key).or_else(|| lookup(&KEY_TEXTS, &self.key)).unwrap_or(self.key.as_str()));
+                text
+            }
+            AcceleratorFormat::Native | AcceleratorFormat::Text | AcceleratorFormat::Localized(_) => {
+                let names = match format {
+                    AcceleratorFormat::Localized(locale) => localized_keys(locale),
+                    _ => &[],
+                };
+                let key = lookup(names, &self.key).or_else(|| lookup(&KEY_TEXTS, &self.key)).unwrap_or(self.key.as_str());
+                let mut texts: Vec<&str> = pressed.map(|(_, name, _)| lookup(names, name).unwrap_or(*name)).collect();
+                texts.push(key);
+                texts.join("+")
+            }
+        }
+    }
+}
+
+fn lookup<'a>(names: &[(&str, &'a str)], key: &str) -> Option<&'a str> {
+    names.iter().find(|(name, _)| *name == key).map(|(_, text)| *text)
+}
+
+/// Returns the localized key names of the language of the locale such as "de-DE" or "de_AT".
+fn localized_keys(locale: &str) -> &'static [(&'static str, &'static str)] {
+    let language = locale.split(['-', '_']).next().unwrap_or_default().to_lowercase();
+    LOCALIZED_KEYS.iter().find(|(name, _)| *name == language).map(|(_, names)| *names).unwrap_or(&[])
+}
+
+impl FromStr for Accelerator {
+    type Err = Error;
+
+    fn from_str(accelerator: &str) -> Result<Self, Self::Err> {
+        let invalid = |message: String| Error::InvalidAccelerator {
+            accelerator: accelerator.to_string(),
+            message,
+        };
+
+        let text = accelerator.trim();
+        /* "+" itself can be the key as in "Ctrl++" */
+        let (modifier_text, key) = if text == "+" {
+            (None, "+")
+        } else if let Some(modifier_text) = text.strip_suffix("++") {
+            (Some(modifier_text), "+")
+        } else {
+            match text.rsplit_once('+') {
+                Some((modifier_text, key)) => (Some(modifier_text), key.trim()),
+                None => (None, text),
+            }
+        };
+
+        if key.is_empty() {
+            return Err(invalid("empty key".to_string()));
+        }
+
+        let mut modifiers = Modifiers::default();
+        for name in modifier_text.into_iter().flat_map(|modifier_text| modifier_text.split('+')).map(str::trim) {
+            if name.is_empty() {
+                return Err(invalid("empty key".to_string()));
+            }
+
+            match modifiers.set(name) {
+                Some(true) => {}
+                Some(false) => return Err(invalid(format!("duplicate modifier \"{}\"", name))),
+                None => return Err(invalid(format!("unknown modifier \"{}\"", name))),
+            }
+        }
+
+        if Modifiers::default().set(key).is_some() {
+            return Err(invalid("no key after modifiers".to_string()));
+        }
+
+        let key = normalize_key(key).ok_or_else(|| invalid(format!("unknown key \"{}\"", key)))?;
+
+        Ok(Self {
+            modifiers,
+            key,
+        })
+    }
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [(self.modifiers.ctrl, "Ctrl"), (self.modifiers.alt, "Alt"), (self.modifiers.shift, "Shift"), (self.modifiers.super_key, "Super")];
+        for (_, name) in modifiers.iter().filter(|(pressed, _)| *pressed) {
+            write!(f, "{}+", name)?;
+        }
+        write!(f, "{}", self.key)
+    }
+}
+
+impl From<Accelerator> for String {
+    fn from(accelerator: Accelerator) -> Self {
+        accelerator.to_string()
+    }
+}
+
+/// Display names of keys for AcceleratorFormat::Text. Other keys are shown as normalized.
+const KEY_TEXTS: [(&str, &str); 16] = [
+    ("Plus", "+"),
+    ("Equal", "="),
+    ("Comma", ","),
+    ("Minus", "-"),
+    ("Period", "."),
+    ("Semicolon", ";"),
+    ("Slash", "/"),
+    ("Backquote", "`"),
+    ("BracketLeft", "["),
+    ("Backslash", "\\"),
+    ("BracketRight", "]"),
+    ("Quote", "'"),
+    ("ArrowLeft", "Left"),
+    ("ArrowUp", "Up"),
+    ("ArrowRight", "Right"),
+    ("ArrowDown", "Down"),
+];
+
+const KEY_SYMBOLS: [(&str, &str); 14] = [
+    ("Enter", "↩"),
+    ("Escape", "⎋"),
+    ("Backspace", "⌫"),
+    ("Delete", "⌦"),
+    ("Tab", "⇥"),
+    ("Space", "␣"),
+    ("ArrowLeft", "←"),
+    ("ArrowUp", "↑"),
+    ("ArrowRight", "→"),
+    ("ArrowDown", "↓"),
+    ("PageUp", "⇞"),
+    ("PageDown", "⇟"),
+    ("Home", "↖"),
+    ("End", "↘"),
+];
+
+/// Localized names of modifiers and keys by language. Ctrl, Alt, Shift and Super come first.
+const LOCALIZED_KEYS: [(&str, &[(&str, &str)]); 4] = [
+    (
+        "de",
+        &[
+            ("Ctrl", "Strg"),
+            ("Shift", "Umschalt"),
+            ("Enter", "Eingabe"),
+            ("Escape", "Esc"),
+            ("Delete", "Entf"),
+            ("Insert", "Einfg"),
+            ("Backspace", "Rücktaste"),
+            ("Space", "Leertaste"),
+            ("Home", "Pos1"),
+            ("End", "Ende"),
+            ("PageUp", "Bild auf"),
+            ("PageDown", "Bild ab"),
+        ],
+    ),
+    (
+        "fr",
+        &[
+            ("Shift", "Maj"),
+            ("Enter", "Entrée"),
+            ("Escape", "Échap"),
+            ("Delete", "Suppr"),
+            ("Insert", "Inser"),
+            ("Backspace", "Retour arrière"),
+            ("Space", "Espace"),
+            ("Home", "Début"),
+            ("End", "Fin"),
+            ("PageUp", "Pg préc"),
+            ("PageDown", "Pg suiv"),
+        ],
+    ),
+    (
+        "es",
+        &[
+            ("Shift", "Mayús"),
+            ("Enter", "Intro"),
+            ("Escape", "Esc"),
+            ("Delete", "Supr"),
+            ("Backspace", "Retroceso"),
+            ("Space", "Espacio"),
+            ("Home", "Inicio"),
+            ("End", "Fin"),
+            ("PageUp", "RePág"),
+            ("PageDown", "AvPág"),
+        ],
+    ),
+    ("it", &[("Shift", "Maiusc"), ("Enter", "Invio"), ("Escape", "Esc"), ("Delete", "Canc"), ("Insert", "Ins"), ("Space", "Spazio"), ("End", "Fine"), ("PageUp", "PgSu"), ("PageDown", "PgGiù")]),
+];
+
+/// Sequence of Accelerators pressed one after another such as "Ctrl+K Ctrl+C".
+///
+/// Keys of a chord are separated by spaces. A single Accelerator is a chord of one key.
+/// With "accelerator" feature, a pending chord is cancelled by Escape or after [`CHORD_TIMEOUT`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AcceleratorChord {
+    accelerators: Vec<Accelerator>,
+}
+
+impl AcceleratorChord {
+    /// Creates a chord from the Accelerators. Returns None if it is empty.
+    pub fn new(accelerators: Vec<Accelerator>) -> Option<Self> {
+        if accelerators.is_empty() {
+            None
+        } else {
+            Some(Self {
+                accelerators,
+            })
+        }
+    }
+
+    pub fn accelerators(&self) -> &[Accelerator] {
+        &self.accelerators
+    }
+
+    /// Returns true if the chord consists of more than one key.
+    pub fn is_sequence(&self) -> bool {
+        self.accelerators.len() > 1
+    }
+
+    /// Returns the display text in the format. Keys are separated by a space.
+    pub fn format(&self, format: &AcceleratorFormat) -> String {
+        self.accelerators.iter().map(|accelerator| accelerator.format(format)).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Returns true if the keys of other chord start this chord.
+    pub fn starts_with(&self, other: &AcceleratorChord) -> bool {
+        self.accelerators.starts_with(&other.accelerators)
+    }
+}
+
+impl FromStr for AcceleratorChord {
+    type Err = Error;
+
+    fn from_str(chord: &str) -> Result<Self, Self::Err> {
+        /* Spaces around "+" as in "Ctrl + S" are allowed in a single Accelerator */
+        if let Ok(accelerator) = chord.parse::<Accelerator>() {
+            return Ok(Self {
+                accelerators: vec![accelerator],
+            });
+        }
+
+        let mut accelerators = Vec::new();
+        for text in chord.split_whitespace() {
+            let accelerator = text.parse::<Accelerator>().map_err(|e| match e {
+                Error::InvalidAccelerator {
+                    message,
+                    ..
+                } => Error::InvalidAccelerator {
+                    accelerator: chord.to_string(),
+                    message,
+                },
+                e => e,
+            })?;
+            accelerators.push(accelerator);
+        }
+
+        Self::new(accelerators).ok_or_else(|| Error::InvalidAccelerator {
+            accelerator: chord.to_string(),
+            message: "empty key".to_string(),
+        })
+    }
+}
+
+impl fmt::Display for AcceleratorChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, accelerator) in self.accelerators.iter().enumerate() {
+            if index > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{}", accelerator)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Accelerator> for AcceleratorChord {
+    fn from(accelerator: Accelerator) -> Self {
+        Self {
+            accelerators: vec![accelerator],
+        }
+    }
+}
+
+impl From<AcceleratorChord> for String {
+    fn from(chord: AcceleratorChord) -> Self {
+        chord.to_string()
+    }
+}
+
+/// Returns the text to display for the accelerator of a MenuItem.
+/// The accelerator is shown as written when the format is AcceleratorFormat::Native or it cannot be parsed.
+#[cfg(not(feature = "mock"))]
+pub(crate) fn display_accelerator(accelerator: &str, format: &AcceleratorFormat) -> String {
+    match (format, accelerator.parse::<AcceleratorChord>()) {
+        (AcceleratorFormat::Native, _) | (_, Err(_)) => accelerator.to_string(),
+        (format, Ok(chord)) => chord.format(format),
+    }
+}
+
+/// Result of a key press to ChordState.
+#[cfg(feature = "accelerator")]
+pub(crate) enum ChordStep<T> {
+    /// The key completes the chord of the target.
+    Matched(T),
+    /// The key starts or continues a chord. The key should be consumed.
+    Pending,
+    /// The pending chord is cancelled by the key. The key should be consumed.
+    Cancelled,
+    /// No chord starts with the key.
+    Unmatched,
+}
+
+/// Keys of the chord being pressed.
+#[cfg(feature = "accelerator")]
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ChordState {
+    pending: Vec<Accelerator>,
+    last_pressed: Option<Instant>,
+}
+
+#[cfg(feature = "accelerator")]
+impl ChordState {
+    pub(crate) fn reset(&mut self) {
+        self.pending.clear();
+        self.last_pressed = None;
+    }
+
+    /// Advances the state by the pressed key. is_pressed tells whether an Accelerator matches the pressed key.
+    /// A chord continuing with the key takes precedence over the one completed by the key.
+    pub(crate) fn press<T>(&mut self, is_pressed: impl Fn(&Accelerator) -> bool, chords: impl IntoIterator<Item = (AcceleratorChord, T)>) -> ChordStep<T> {
+        self.press_at(Instant::now(), is_pressed, chords)
+    }
+
+    /// Same as press, but the key is pressed at now.
+    fn press_at<T>(&mut self, now: Instant, is_pressed: impl Fn(&Accelerator) -> bool, chords: impl IntoIterator<Item = (AcceleratorChord, T)>) -> ChordStep<T> {
+        if self.last_pressed.is_some_and(|last_pressed| now.saturating_duration_since(last_pressed) > CHORD_TIMEOUT) {
+            self.reset();
+        }
+
+        let depth = self.pending.len();
+        if depth > 0 && is_pressed(&cancel_accelerator()) {
+            self.reset();
+            return ChordStep::Cancelled;
+        }
+
+        let mut matched = None;
+        let mut next = None;
+        for (chord, target) in chords {
+            let accelerators = chord.accelerators();
+            if accelerators.len() <= depth || accelerators[..depth] != self.pending[..] || !is_pressed(&accelerators[depth]) {
+                continue;
+            }
+
+            if accelerators.len() == depth + 1 {
+                matched.get_or_insert(target);
+            } else {
+                next.get_or_insert_with(|| accelerators[depth].clone());
+            }
+        }
+
+        if let Some(accelerator) = next {
+            self.pending.push(accelerator);
+            self.last_pressed = Some(now);
+            return ChordStep::Pending;
+        }
+
+        self.reset();
+        match matched {
+            Some(target) => ChordStep::Matched(target),
+            None if depth > 0 => ChordStep::Cancelled,
+            None => ChordStep::Unmatched,
+        }
+    }
+}
+
+#[cfg(feature = "accelerator")]
+fn cancel_accelerator() -> Accelerator {
+    Accelerator {
+        modifiers: Modifiers::default(),
+        key: "Escape".to_string(),
+    }
+}
+
+fn normalize_key(key: &str) -> Option<String> {
+    let upper_key = key.to_uppercase();
+
+    if upper_key.len() == 1 && upper_key.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Some(upper_key);
+    }
+
+    if let Some(number) = upper_key.strip_prefix('F').and_then(|number| number.parse::<u8>().ok()) {
+        if (1..=24).contains(&number) {
+            return Some(format!("F{}", number));
+        }
+    }
+
+    if let Some((_, name)) = KEY_ALIASES.iter().find(|(alias, _)| *alias == upper_key) {
+        return Some(name.to_string());
+    }
+
+    NAMED_KEYS.iter().find(|name| name.to_uppercase() == upper_key).map(|name| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(accelerator: &str) -> Accelerator {
+        accelerator.parse().unwrap()
+    }
+
+    fn modifiers(ctrl: bool, alt: bool, shift: bool, super_key: bool) -> Modifiers {
+        Modifiers {
+            ctrl,
+            alt,
+            shift,
+            super_key,
+        }
+    }
+
+    #[test]
+    fn parses_modifiers_and_key() {
+        let accelerator = parse("Ctrl+Shift+S");
+        assert_eq!(accelerator.modifiers(), modifiers(true, false, true, false));
+        assert_eq!(accelerator.key(), "S");
+
+        assert_eq!(parse("F11").modifiers(), Modifiers::default());
+        assert_eq!(parse("f11").key(), "F11");
+        assert_eq!(parse("Ctrl + S"), parse("Ctrl+S"));
+    }
+
+    #[test]
+    fn parses_plus_as_key() {
+        assert_eq!(parse("Ctrl++"), Accelerator::new(modifiers(true, false, false, false), "Plus").unwrap());
+        assert_eq!(parse("Ctrl+Shift++").modifiers(), modifiers(true, false, true, false));
+        assert_eq!(parse("+").key(), "Plus");
+        assert_eq!(parse("Ctrl+Plus"), parse("Ctrl++"));
+    }
+
+    #[test]
+    fn parses_modifier_aliases_in_any_case() {
+        let table = [
+            ("control+a", modifiers(true, false, false, false)),
+            ("CmdOrCtrl+A", modifiers(true, false, false, false)),
+            ("COMMANDORCONTROL+A", modifiers(true, false, false, false)),
+            ("Option+A", modifiers(false, true, false, false)),
+            ("shift+A", modifiers(false, false, true, false)),
+            ("Cmd+A", modifiers(false, false, false, true)),
+            ("Meta+A", modifiers(false, false, false, true)),
+            ("win+A", modifiers(false, false, false, true)),
+            ("Super+Alt+Ctrl+Shift+A", modifiers(true, true, true, true)),
+        ];
+
+        for (text, expected) in table {
+            assert_eq!(parse(text).modifiers(), expected, "{}", text);
+        }
+    }
+
+    #[test]
+    fn parses_key_aliases() {
+        let table = [("Esc", "Escape"), ("return", "Enter"), ("Del", "Delete"), ("PgDn", "PageDown"), ("Left", "ArrowLeft"), (",", "Comma"), ("=", "Equal"), ("arrowup", "ArrowUp")];
+
+        for (key, expected) in table {
+            assert_eq!(parse(&format!("Ctrl+{}", key)).key(), expected, "{}", key);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_accelerators() {
+        let table = [
+            ("", "empty key"),
+            ("Ctrl+", "empty key"),
+            ("Ctrl++S", "empty key"),
+            ("Ctrl+Shift", "no key after modifiers"),
+            ("Ctrl+Foo", "unknown key \"Foo\""),
+            ("F25", "unknown key \"F25\""),
+            ("Hyper+S", "unknown modifier \"Hyper\""),
+            ("Ctrl+Control+S", "duplicate modifier \"Control\""),
+            ("Cmd+Win+S", "duplicate modifier \"Win\""),
+        ];
+
+        for (text, expected) in table {
+            match text.parse::<Accelerator>() {
+                Err(Error::InvalidAccelerator {
+                    accelerator,
+                    message,
+                }) => {
+                    assert_eq!(accelerator, text);
+                    assert_eq!(message, expected, "{}", text);
+                }
+                result => panic!("{} parsed as {:?}", text, result),
+            }
+        }
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let table = [("ctrl+shift+s", "Ctrl+Shift+S"), ("Shift+Super+Alt+Ctrl+F5", "Ctrl+Alt+Shift+Super+F5"), ("CmdOrCtrl++", "Ctrl+Plus"), ("Alt+esc", "Alt+Escape"), ("Ctrl+/", "Ctrl+Slash")];
+
+        for (text, expected) in table {
+            let accelerator = parse(text);
+            assert_eq!(accelerator.to_string(), expected);
+            assert_eq!(parse(&accelerator.to_string()), accelerator);
+        }
+    }
+
+    #[test]
+    fn chord_display_round_trips() {
+        let chord: AcceleratorChord = "ctrl+k  ctrl+c".parse().unwrap();
+        assert!(chord.is_sequence());
+        assert_eq!(chord.to_string(), "Ctrl+K Ctrl+C");
+        assert_eq!(chord.to_string().parse::<AcceleratorChord>().unwrap(), chord);
+
+        let single: AcceleratorChord = "Ctrl + S".parse().unwrap();
+        assert!(!single.is_sequence());
+        assert!(chord.starts_with(&"Ctrl+K".parse().unwrap()));
+    }
+
+    #[test]
+    fn chord_errors_report_whole_chord() {
+        assert!(matches!("Ctrl+K Ctrl+Foo".parse::<AcceleratorChord>(), Err(Error::InvalidAccelerator { accelerator, .. }) if accelerator == "Ctrl+K Ctrl+Foo"));
+        assert!(matches!(" ".parse::<AcceleratorChord>(), Err(Error::InvalidAccelerator { .. })));
+    }
+
+    #[test]
+    fn formats_text_and_symbols() {
+        let table = [
+            ("Ctrl+Shift+S", "Ctrl+Shift+S", "⌃⇧S"),
+            ("Super+Alt+Enter", "Alt+Super+Enter", "⌥⌘↩"),
+            ("Ctrl++", "Ctrl++", "⌃+"),
+            ("Ctrl+ArrowUp", "Ctrl+Up", "⌃↑"),
+            ("Shift+Comma", "Shift+,", "⇧,"),
+            ("F5", "F5", "F5"),
+        ];
+
+        for (text, expected_text, expected_symbols) in table {
+            let accelerator = parse(text);
+            assert_eq!(accelerator.format(&AcceleratorFormat::Text), expected_text);
+            assert_eq!(accelerator.format(&AcceleratorFormat::Native), expected_text);
+            assert_eq!(accelerator.format(&AcceleratorFormat::Symbols), expected_symbols);
+        }
+    }
+
+    #[test]
+    fn formats_localized_names() {
+        let table = [
+            ("de", "Ctrl+Shift+Delete", "Strg+Umschalt+Entf"),
+            ("de-DE", "Ctrl+Home", "Strg+Pos1"),
+            ("de_AT", "Alt+PageUp", "Alt+Bild auf"),
+            ("DE", "Ctrl+S", "Strg+S"),
+            ("fr-FR", "Ctrl+Shift+Enter", "Ctrl+Maj+Entrée"),
+            ("es", "Shift+Space", "Mayús+Espacio"),
+            ("it-IT", "Ctrl+End", "Ctrl+Fine"),
+            /* Keys without a localized name are shown as Text */
+            ("de", "Ctrl+ArrowLeft", "Strg+Left"),
+            /* Unsupported languages fall back to Text */
+            ("ja-JP", "Ctrl+Shift+Delete", "Ctrl+Shift+Delete"),
+            ("", "Ctrl+Plus", "Ctrl++"),
+        ];
+
+        for (locale, text, expected) in table {
+            assert_eq!(parse(text).format(&AcceleratorFormat::Localized(locale.to_string())), expected, "{} {}", locale, text);
+        }
+    }
+
+    #[test]
+    fn formats_chord_keys_with_space() {
+        let chord: AcceleratorChord = "Ctrl+K Ctrl+Delete".parse().unwrap();
+        assert_eq!(chord.format(&AcceleratorFormat::Text), "Ctrl+K Ctrl+Delete");
+        assert_eq!(chord.format(&AcceleratorFormat::Symbols), "⌃K ⌃⌦");
+        assert_eq!(chord.format(&AcceleratorFormat::Localized("de-DE".to_string())), "Strg+K Strg+Entf");
+    }
+
+    #[cfg(not(feature = "mock"))]
+    #[test]
+    fn display_accelerator_keeps_native_and_invalid_as_written() {
+        assert_eq!(display_accelerator("ctrl+shift+s", &AcceleratorFormat::Native), "ctrl+shift+s");
+        assert_eq!(display_accelerator("ctrl+shift+s", &AcceleratorFormat::Text), "Ctrl+Shift+S");
+        assert_eq!(display_accelerator("ctrl+shift+s", &AcceleratorFormat::Localized("de-DE".to_string())), "Strg+Umschalt+S");
+        assert_eq!(display_accelerator("Ctrl+Foo", &AcceleratorFormat::Symbols), "Ctrl+Foo");
+    }
+
+    #[cfg(feature = "accelerator")]
+    fn chords() -> Vec<(AcceleratorChord, &'static str)> {
+        ["Ctrl+K Ctrl+C", "Ctrl+K Ctrl+U", "Ctrl+K", "Ctrl+S"].into_iter().map(|chord| (chord.parse().unwrap(), chord)).collect()
+    }
+
+    /// Presses the key against chords() at the time and returns the matched chord, "pending", "cancelled" or "unmatched".
+    #[cfg(feature = "accelerator")]
+    fn press(state: &mut ChordState, key: &str, now: Instant) -> &'static str {
+        let pressed = parse(key);
+        match state.press_at(now, |accelerator| *accelerator == pressed, chords()) {
+            ChordStep::Matched(chord) => chord,
+            ChordStep::Pending => "pending",
+            ChordStep::Cancelled => "cancelled",
+            ChordStep::Unmatched => "unmatched",
+        }
+    }
+
+    #[cfg(feature = "accelerator")]
+    #[test]
+    fn chord_matches_second_key() {
+        let mut state = ChordState::default();
+        let now = Instant::now();
+
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+        assert_eq!(press(&mut state, "Ctrl+U", now + Duration::from_millis(100)), "Ctrl+K Ctrl+U");
+        assert_eq!(press(&mut state, "Ctrl+S", now + Duration::from_millis(200)), "Ctrl+S");
+        assert_eq!(press(&mut state, "Ctrl+Q", now + Duration::from_millis(300)), "unmatched");
+    }
+
+    #[cfg(feature = "accelerator")]
+    #[test]
+    fn chord_prefix_takes_precedence_over_single_accelerator() {
+        let mut state = ChordState::default();
+        let now = Instant::now();
+
+        /* "Ctrl+K" alone is also an accelerator, but the chords starting with it win */
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+        assert_eq!(press(&mut state, "Ctrl+C", now), "Ctrl+K Ctrl+C");
+    }
+
+    #[cfg(feature = "accelerator")]
+    #[test]
+    fn chord_expires_after_timeout() {
+        let mut state = ChordState::default();
+        let now = Instant::now();
+
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+        assert_eq!(press(&mut state, "Ctrl+C", now + CHORD_TIMEOUT), "Ctrl+K Ctrl+C");
+
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+        /* The second key starts over after the timeout, so Ctrl+C alone matches nothing */
+        assert_eq!(press(&mut state, "Ctrl+C", now + CHORD_TIMEOUT + Duration::from_millis(1)), "unmatched");
+        assert_eq!(press(&mut state, "Ctrl+S", now + CHORD_TIMEOUT * 2), "Ctrl+S");
+    }
+
+    #[cfg(feature = "accelerator")]
+    #[test]
+    fn escape_cancels_pending_chord() {
+        let mut state = ChordState::default();
+        let now = Instant::now();
+
+        assert_eq!(press(&mut state, "Escape", now), "unmatched");
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+        assert_eq!(press(&mut state, "Escape", now), "cancelled");
+        assert_eq!(press(&mut state, "Ctrl+C", now), "unmatched");
+    }
+
+    #[cfg(feature = "accelerator")]
+    #[test]
+    fn non_matching_second_key_resets_chord() {
+        let mut state = ChordState::default();
+        let now = Instant::now();
+
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+        assert_eq!(press(&mut state, "Ctrl+S", now), "cancelled");
+        /* The key cancelling the chord is consumed and does not start over */
+        assert_eq!(press(&mut state, "Ctrl+U", now), "unmatched");
+        assert_eq!(press(&mut state, "Ctrl+K", now), "pending");
+    }
+}
@@ -14,6 +14,32 @@ pub enum Corner {
     DoNotRound,
 }
 
+/// How MenuItems sharing the same accelerator are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AcceleratorConflictPolicy {
+    /// MenuBuilder.build(), Menu.append() and Menu.insert() fail with Error::AcceleratorConflict.
+    Error,
+    /// Conflicts are kept in the main Menu. Take them by Menu.take_accelerator_conflicts().
+    #[default]
+    Warn,
+    /// Conflicts are not checked.
+    Allow,
+}
+
+/// How accelerators are displayed in MenuItems.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AcceleratorFormat {
+    /// On Windows, shown as written in MenuItem.accelerator. On Linux, formatted by Gtk.
+    #[default]
+    Native,
+    /// Normalized text such as "Ctrl+Shift+S".
+    Text,
+    /// Modifier and key symbols such as "⌃⇧S".
+    Symbols,
+    /// Key names of the language such as "Strg+S" for "de" or "de-DE". Falls back to Text for unsupported languages.
+    Localized(String),
+}
+
 /// Menu configuration for Theme, Size and Color.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -24,6 +50,20 @@ pub struct Config {
     pub corner: Corner,
     pub font: MenuFont,
     pub icon: Option<IconSettings>,
+    /// On Linux, accelerators of the window's accel groups are also checked.
+    #[serde(default)]
+    pub accelerator_conflict: AcceleratorConflictPolicy,
+    #[serde(default)]
+    pub accelerator_format: AcceleratorFormat,
+    /// Type-ahead search while Menu is shown. Disabled when None. Only on Linux.
+    #[serde(default)]
+    pub type_ahead: Option<TypeAheadSettings>,
+    /// Search field at the top of the main Menu. Disabled when None. Set by MenuBuilder.search(). Only on Linux.
+    #[serde(default)]
+    pub search: Option<SearchSettings>,
+    /// Treats "_" on Linux and "&" on Windows before a letter in labels as the mnemonic. Labels are shown as written when false.
+    #[serde(default)]
+    pub mnemonic: bool,
 }
 
 impl Default for Config {
@@ -35,6 +75,11 @@ impl Default for Config {
             corner: Corner::Round,
             font: MenuFont::default(),
             icon: Some(IconSettings::default()),
+            accelerator_conflict: AcceleratorConflictPolicy::default(),
+            accelerator_format: AcceleratorFormat::default(),
+            type_ahead: None,
+            search: None,
+            mnemonic: false,
         }
     }
 }
@@ -117,6 +162,13 @@ pub struct ColorScheme {
     pub background_color: u32,
     /// MenuItem hover color.
     pub hover_background_color: u32,
+    /// MenuItem description text color. Defaults to a gray readable on both themes when missing in serialized settings.
+    #[serde(default = "default_description_color")]
+    pub description: u32,
+}
+
+fn default_description_color() -> u32 {
+    0x8a8a8a
 }
 
 /// ## Default colors for Dark Theme.
@@ -130,6 +182,7 @@ pub struct ColorScheme {
 ///   disabled: 0x565659,
 ///   background_color: 0x252526,
 ///   hover_background_color: 0x3b3a3a,
+///   description: 0xe7e0e08c,
 /// };
 pub const DEFAULT_DARK_COLOR_SCHEME: ColorScheme = ColorScheme {
     color: 0xe7e0e0,
@@ -139,6 +192,7 @@ pub const DEFAULT_DARK_COLOR_SCHEME: ColorScheme = ColorScheme {
     disabled: 0x565659,
     background_color: 0x252526,
     hover_background_color: 0x3b3a3a,
+    description: 0xe7e0e08c,
 };
 
 /// ## Default colors for Light Theme.
@@ -151,6 +205,7 @@ pub const DEFAULT_DARK_COLOR_SCHEME: ColorScheme = ColorScheme {
 ///   disabled: 0xc5c1c1,
 ///   background_color: 0xFFFFFF,
 ///   hover_background_color: 0xefefef,
+///   description: 0x4947478c,
 /// };
 /// ```
 pub const DEFAULT_LIGHT_COLOR_SCHEME: ColorScheme = ColorScheme {
@@ -161,6 +216,7 @@ pub const DEFAULT_LIGHT_COLOR_SCHEME: ColorScheme = ColorScheme {
     disabled: 0xc5c1c1,
     background_color: 0xFFFFFF,
     hover_background_color: 0xefefef,
+    description: 0x4947478c,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -174,6 +230,8 @@ pub const DEFAULT_LIGHT_COLOR_SCHEME: ColorScheme = ColorScheme {
 ///   light_font_size: 12.0,
 ///   dark_font_weight: Normal,
 ///   light_font_weight: Normal,
+///   dark_description_font_size: 10.0,
+///   light_description_font_size: 10.0,
 ///  ```
 pub struct MenuFont {
     /// Font family.
@@ -186,6 +244,16 @@ pub struct MenuFont {
     pub dark_font_weight: FontWeight,
     /// Font weight for Light theme.
     pub light_font_weight: FontWeight,
+    /// Font size of MenuItem description for Dark theme.
+    #[serde(default = "default_description_font_size")]
+    pub dark_description_font_size: f32,
+    /// Font size of MenuItem description for Light theme.
+    #[serde(default = "default_description_font_size")]
+    pub light_description_font_size: f32,
+}
+
+fn default_description_font_size() -> f32 {
+    10.0
 }
 
 impl Default for MenuFont {
@@ -196,6 +264,8 @@ impl Default for MenuFont {
             light_font_size: 12.0,
             dark_font_weight: FontWeight::Normal,
             light_font_weight: FontWeight::Normal,
+            dark_description_font_size: default_description_font_size(),
+            light_description_font_size: default_description_font_size(),
         }
     }
 }
@@ -217,6 +287,50 @@ pub enum FontWeight {
     Bold,
 }
 
+/// Type-ahead settings.
+///
+/// Typed characters build a prefix which highlights the next visible and enabled MenuItem whose label starts with it.
+/// Typing the same character repeatedly cycles through the MenuItems starting with it.
+/// Mnemonics are not available while type-ahead is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeAheadSettings {
+    /// Milliseconds of idle time after which the typed prefix is discarded.
+    pub reset_ms: u64,
+    /// Whether to search MenuItems in submenus as well.
+    pub search_submenus: bool,
+}
+
+impl Default for TypeAheadSettings {
+    fn default() -> Self {
+        Self {
+            reset_ms: 1000,
+            search_submenus: false,
+        }
+    }
+}
+
+/// Search field settings.
+///
+/// Typed text filters the MenuItems of the Menu and its submenus with fuzzy matching on their labels and keywords.
+/// Matches are listed in place of the MenuItems with their submenu path, and Enter activates the top match.
+/// Type-ahead and mnemonics are not available while the search field is enabled.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchSettings {
+    /// Text shown in the empty search field.
+    pub placeholder: String,
+    /// Maximum number of matches listed at once.
+    pub max_results: usize,
+}
+
+impl Default for SearchSettings {
+    fn default() -> Self {
+        Self {
+            placeholder: "Search".to_string(),
+            max_results: 10,
+        }
+    }
+}
+
 /// Icon settings.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IconSettings {
@@ -224,6 +338,10 @@ pub struct IconSettings {
     pub check: Option<MenuIcon>,
     /// Icon to override the default arrow SVG for submenu item.
     pub arrow: Option<MenuIcon>,
+    /// Icon to override the default mark of mixed checkbox item.
+    /// On Linux, Data and Svg icons are used only when check is also a Data or Svg icon. Otherwise Gtk's inconsistent look is used.
+    #[serde(default)]
+    pub mixed: Option<MenuIcon>,
     /// Whether to reserve space for icons regardless of their actual presence.
     pub reserve_icon_size: bool,
     /// Left and right margins of the icons set to menu items.
@@ -235,6 +353,7 @@ impl Default for IconSettings {
         Self {
             check: None,
             arrow: None,
+            mixed: None,
             reserve_icon_size: true,
             horizontal_margin: None,
         }
@@ -249,6 +368,7 @@ pub struct RGBA {
     pub a: f32,
 }
 
+#[cfg(not(feature = "mock"))]
 pub(crate) fn to_hex_string(color: u32) -> String {
     if has_alpha(color) {
         format!("#{:08x}", color)
@@ -257,7 +377,7 @@ pub(crate) fn to_hex_string(color: u32) -> String {
     }
 }
 
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
 pub(crate) fn to_rgba_string(color: u32) -> String {
     let rgba = rgba_from_hex(color);
     format!("rgba({}, {}, {}, {:.2})", rgba.r, rgba.g, rgba.b, rgba.a)
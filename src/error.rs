@@ -0,0 +1,99 @@
+use crate::spec::SpecError;
+use serde::{Deserialize, Serialize};
+use std::{fmt, path::PathBuf};
+
+/// Error of wcpopup.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Accelerator text cannot be parsed.
+    InvalidAccelerator {
+        accelerator: String,
+        message: String,
+    },
+    /// Icon file cannot be loaded.
+    IconLoad {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// Icon data does not match its size.
+    InvalidIcon(String),
+    /// SVG icon cannot be parsed or rendered.
+    InvalidSvg(String),
+    /// Another MenuItem in the Menu has the same id.
+    DuplicateId(String),
+    /// MenuItems share the same accelerator.
+    AcceleratorConflict(AcceleratorConflict),
+    /// Config has an invalid value.
+    InvalidConfig(String),
+    /// MenuSpec is malformed.
+    InvalidSpec(SpecError),
+    /// Error from the window system.
+    Platform(Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Accelerator shared by MenuItems. other_id is empty when the accelerator is used by the window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AcceleratorConflict {
+    pub accelerator: String,
+    pub id: String,
+    pub other_id: String,
+}
+
+impl fmt::Display for AcceleratorConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.other_id.is_empty() {
+            write!(f, "accelerator \"{}\" of \"{}\" is already used by the window", self.accelerator, self.id)
+        } else {
+            write!(f, "accelerator \"{}\" of \"{}\" is already used by \"{}\"", self.accelerator, self.id, self.other_id)
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidAccelerator {
+                accelerator,
+                message,
+            } => write!(f, "invalid accelerator \"{}\": {}", accelerator, message),
+            Error::IconLoad {
+                path,
+                source,
+            } => write!(f, "failed to load icon {}: {}", path.display(), source),
+            Error::InvalidIcon(message) => write!(f, "invalid icon: {}", message),
+            Error::InvalidSvg(message) => write!(f, "invalid svg: {}", message),
+            Error::DuplicateId(id) => write!(f, "duplicate menu item id \"{}\"", id),
+            Error::AcceleratorConflict(conflict) => conflict.fmt(f),
+            Error::InvalidConfig(message) => write!(f, "invalid config: {}", message),
+            Error::InvalidSpec(e) => e.fmt(f),
+            Error::Platform(e) => write!(f, "platform error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IconLoad {
+                source,
+                ..
+            } => Some(source),
+            Error::InvalidSpec(e) => Some(e),
+            Error::Platform(e) => Some(e.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<AcceleratorConflict> for Error {
+    fn from(conflict: AcceleratorConflict) -> Self {
+        Error::AcceleratorConflict(conflict)
+    }
+}
+
+impl From<SpecError> for Error {
+    fn from(e: SpecError) -> Self {
+        Error::InvalidSpec(e)
+    }
+}
@@ -42,24 +42,86 @@
 //! }
 //! ```
 //!
+//! MenuBuilder.build(), Menu.append(), Menu.insert() and MenuItem.set_icon() return [`Error`] when an accelerator, icon, id or Config is invalid.
+//!
+//! Accelerators can be given as text or as [`Accelerator`], which normalizes modifiers such as "cmdorctrl+s" to "Ctrl+S".
+//! Chords such as "Ctrl+K Ctrl+C" are parsed as [`AcceleratorChord`].
+//! Accelerators shared by multiple MenuItems are reported according to [`config::AcceleratorConflictPolicy`].
+//!
 //! Call Menu.popup_at() to show Menu and receive the selected MenuItem using MenuEvent.
 //! ```rust
 //! fn show_context_menu(x:i32, y:i32) {
 //!     menu.popup_at(x, y);
 //! }
 //!
-//! if let Ok(event) = MenuEvent::receiver().try_recv() {
-//!     let selected_menu_item = event.item;
+//! if let Ok(MenuEvent::Selected { item, .. }) = MenuEvent::receiver().try_recv() {
+//!     let selected_menu_item = item;
 //! }
 //! ```
 //!
-//! Or call Menu.popup_at_async() to show Menu and wait asynchronously for a selected MenuItem.
+//! Besides Selected, MenuEvent notifies Opened, Closed, Highlighted, SubmenuOpened, SubmenuClosed and Toggled during a popup.
+//!
+//! MenuEvent::receiver() receives events from all Menus.
+//! Use Menu.subscribe() or Menu.receiver() to receive events only from a specific Menu.
+//! ```rust
+//! let subscription = menu.subscribe(|event| {
+//!     if let MenuEvent::Selected { item, .. } = event {
+//!         let selected_menu_item = item;
+//!     }
+//! });
+//! subscription.unsubscribe();
+//!
+//! if let Ok(MenuEvent::Selected { item, .. }) = menu.receiver().try_recv() {
+//!     let selected_menu_item = item;
+//! }
+//! ```
+//!
+//! Or call Menu.popup_at_async() to show Menu and wait asynchronously for the PopupResult.
 //! ```rust
 //! async fn show_context_menu(x:i32, y:i32) {
-//!     let selected_menu_item = menu.popup_at(x, y).await;
+//!     match menu.popup_at_async(x, y).await {
+//!         PopupResult::Selected(item) | PopupResult::Accelerator(item) => {}
+//!         PopupResult::Dismissed(reason) => {}
+//!     }
 //! }
 //! ```
 //!
+//! With Menu.popup_at(), MenuEvent::Closed notifies the same result as CloseReason.
+//!
+//! Call Menu.close() to close the shown Menu from code. The popup is resolved as `DismissReason::Closed`.
+//!
+//! The async APIs do not depend on a specific runtime. MenuEvent::stream() and Menu.stream() return a `futures_core::Stream` of MenuEvent.
+//! ```rust
+//! let mut stream = menu.stream();
+//! while let Some(event) = stream.next().await {}
+//! ```
+//!
+//! Use "tokio" feature to receive events of a Menu with `tokio::sync::mpsc` by Menu.tokio_receiver().
+//! ```
+//! features = ["tokio"]
+//! ```
+//!
+//! ## Menu definition
+//! Menu can be defined declaratively by [`spec::MenuSpec`] and built with MenuBuilder::from_spec().
+//! Use "json" or "toml" feature to build Menu directly from text by Menu::from_json() or Menu::from_toml().
+//! ```
+//! features = ["json"]
+//! ```
+//!
+//! Menu.to_model() returns the current state of Menu as MenuSpec without any handle. Menu::from_model() rebuilds the same Menu from it.
+//!
+//! ## Search
+//! Menu.search() finds MenuItems in Menu and its submenus by fuzzy matching on labels and [`MenuItem`] keywords. See [`search`].
+//! On Linux, MenuBuilder.search() adds a search field at the top of Menu which lists the matches and activates the top one by Enter.
+//!
+//! ## Testing
+//! Use "mock" feature to replace the Gtk/Windows backend with an in-memory one which needs neither display nor window.
+//! User actions can be simulated by Menu.activate_by_id(), Menu.highlight() and Menu.dismiss().
+//! The same methods and Menu.is_open() are available on Gtk and Windows to drive a shown Menu in end-to-end tests. They go through the same code path as user input.
+//! ```
+//! features = ["mock"]
+//! ```
+//!
 //! ## Platform-specific notes
 //! #### Windows
 //! WebView2 may receive all keyboard input instead of its parent window([#1703](https://github.com/MicrosoftEdge/WebView2Feedback/issues/1703)). You can disable it by either
@@ -75,19 +137,44 @@
 //!
 //! #### Linux
 //! Gtk3 is required. MenuItem's text color is applied to SVG icon if the SVG file contains the "symbolic" term as the last component of the file name.
+//!
+//! Theme::System follows the `org.freedesktop.appearance color-scheme` setting of the settings portal,
+//! then `gtk-application-prefer-dark-theme`, then whether the Gtk theme name contains "dark".
+//! Use [`detect_system_color_scheme`] to see which one was used.
+mod accelerator;
 pub mod config;
+mod error;
+mod mnemonic;
 mod platform;
+pub mod search;
+pub mod spec;
+mod validation;
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
-    sync::{LazyLock, OnceLock},
+    pin::Pin,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, LazyLock, Mutex, RwLock,
+    },
+    task::{Context, Poll},
 };
 
-#[cfg(target_os = "linux")]
+#[cfg(feature = "accelerator")]
+pub use accelerator::CHORD_TIMEOUT;
+pub use accelerator::{Accelerator, AcceleratorChord, Modifiers};
+use async_channel::{bounded, unbounded, Receiver, Sender};
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
 use config::Corner;
+pub use error::{AcceleratorConflict, Error};
+use futures_core::Stream;
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
+pub use platform::platform_impl::{detect_system_color_scheme, detect_system_color_scheme_on, ColorSchemeSource, SystemColorScheme};
 pub use platform::platform_impl::{Menu, MenuBuilder, MenuItem};
 use serde::{Deserialize, Serialize};
-use smol::channel::{unbounded, Receiver, Sender};
 
+/* Mock backend has no theme */
+#[cfg(not(feature = "mock"))]
 #[derive(Debug, PartialEq, Eq)]
 enum ThemeChangeFactor {
     SystemSetting,
@@ -103,11 +190,53 @@ pub enum MenuType {
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Copy)]
 pub enum MenuItemType {
+    #[serde(alias = "text")]
     Text,
+    #[serde(alias = "checkbox")]
     Checkbox,
+    #[serde(alias = "radio")]
     Radio,
+    #[serde(alias = "submenu")]
     Submenu,
+    #[serde(alias = "separator")]
     Separator,
+    /// Section heading such as "Recent files". It is never selected, activated nor reached by keyboard.
+    #[serde(alias = "header")]
+    Header,
+}
+
+/// Check state of a checkbox or radio MenuItem.
+/// Mixed is an indeterminate state such as a "Bold" toggle over a selection with mixed formatting. Clicking a mixed MenuItem checks it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CheckState {
+    #[default]
+    Unchecked,
+    Checked,
+    Mixed,
+}
+
+impl CheckState {
+    /// Returns true only for Checked.
+    pub fn is_checked(self) -> bool {
+        self == CheckState::Checked
+    }
+}
+
+impl From<bool> for CheckState {
+    fn from(checked: bool) -> Self {
+        if checked {
+            CheckState::Checked
+        } else {
+            CheckState::Unchecked
+        }
+    }
+}
+
+impl MenuItemType {
+    /// Returns false for separators and headers, which cannot be highlighted, activated or reached by keyboard.
+    pub(crate) fn is_selectable(self) -> bool {
+        !matches!(self, MenuItemType::Separator | MenuItemType::Header)
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -178,54 +307,300 @@ impl MenuIcon {
     }
 }
 
+/// Reason the Menu was closed without selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DismissReason {
+    /// Escape key is pressed.
+    Escape,
+    /// Mouse is clicked outside of the Menu.
+    ClickOutside,
+    /// Window is deactivated or another window takes the input.
+    FocusLost,
+    /// Mouse wheel is scrolled. Windows only.
+    Scroll,
+    /// Menu is destroyed while shown.
+    Destroyed,
+    /// Menu is shown again before the popup finishes.
+    Replaced,
+    /// Menu.close() is called.
+    Closed,
+}
+
+/// Result of a popup.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum PopupResult {
+    /// MenuItem is selected by click or Enter key.
+    Selected(MenuItem),
+    /// MenuItem is activated by its accelerator.
+    Accelerator(MenuItem),
+    /// Menu is closed without selection.
+    Dismissed(DismissReason),
+}
+
+impl PopupResult {
+    /// Selected MenuItem if any.
+    pub fn item(&self) -> Option<&MenuItem> {
+        match self {
+            PopupResult::Selected(item) | PopupResult::Accelerator(item) => Some(item),
+            PopupResult::Dismissed(_) => None,
+        }
+    }
 
-pub struct MenuEvent {
-    pub item: MenuItem,
+    /// Converts into the selected MenuItem if any.
+    pub fn into_item(self) -> Option<MenuItem> {
+        match self {
+            PopupResult::Selected(item) | PopupResult::Accelerator(item) => Some(item),
+            PopupResult::Dismissed(_) => None,
+        }
+    }
 }
 
-pub(crate) struct InnerMenuEvent {
-    pub(crate) item: Option<MenuItem>,
+/// Reason the Menu was closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CloseReason {
+    /// Closed by selecting a MenuItem.
+    Selected,
+    /// Closed by an accelerator.
+    Accelerator,
+    /// Closed without selection.
+    Dismissed(DismissReason),
+}
+
+impl From<&PopupResult> for CloseReason {
+    fn from(result: &PopupResult) -> Self {
+        match result {
+            PopupResult::Selected(_) => CloseReason::Selected,
+            PopupResult::Accelerator(_) => CloseReason::Accelerator,
+            PopupResult::Dismissed(reason) => CloseReason::Dismissed(*reason),
+        }
+    }
+}
+
+/// How a MenuItem is activated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ActivationSource {
+    /// Click or Enter key on the shown Menu.
+    Menu,
+    /// Accelerator pressed while Menu is shown.
+    Accelerator,
+    /// Accelerator pressed on the window while Menu is closed. See Menu.set_window_shortcuts().
+    Shortcut,
+    /// Menu.activate_by_id() is called.
+    Program,
+}
+
+/// Events sent during the lifecycle of a popup.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MenuEvent {
+    /// MenuItem is selected by click, Enter key or accelerator.
+    Selected {
+        item: MenuItem,
+        source: ActivationSource,
+    },
+    /// Menu is shown.
+    Opened,
+    /// Menu is hidden. The reason carries the same data as PopupResult.
+    Closed {
+        reason: CloseReason,
+    },
+    /// MenuItem is hovered or focused by keyboard. MenuItem.tooltip can be shown in a status bar.
+    Highlighted(MenuItem),
+    /// Submenu of the MenuItem is shown.
+    SubmenuOpened(MenuItem),
+    /// Submenu of the MenuItem is hidden.
+    SubmenuClosed(MenuItem),
+    /// Check state of Checkbox or Radio MenuItem is changed. A mixed checkbox is checked when clicked.
+    Toggled {
+        item: MenuItem,
+        check_state: CheckState,
+    },
 }
 
 pub type MenuEventReceiver = Receiver<MenuEvent>;
-type MenuEventHandler = std::boxed::Box<dyn Fn(MenuEvent) + Send + Sync + 'static>;
-type InnerMenuEventReceiver = Receiver<InnerMenuEvent>;
+type MenuEventHandler = Arc<dyn Fn(MenuEvent) + Send + Sync + 'static>;
+pub(crate) type PopupSender = Sender<PopupResult>;
+
+#[derive(Clone)]
+enum Subscriber {
+    Handler(MenuEventHandler),
+    Channel(Sender<MenuEvent>),
+    #[cfg(feature = "tokio")]
+    Tokio(tokio::sync::mpsc::UnboundedSender<MenuEvent>),
+}
 
 static MENU_CHANNEL: LazyLock<(Sender<MenuEvent>, MenuEventReceiver)> = LazyLock::new(unbounded);
-static INNER_MENU_CHANNEL: LazyLock<(Sender<InnerMenuEvent>, InnerMenuEventReceiver)> = LazyLock::new(unbounded);
-static MENU_EVENT_HANDLER: OnceLock<Option<MenuEventHandler>> = OnceLock::new();
+/* Events other than Selected are buffered only after the global receiver is requested */
+static MENU_CHANNEL_IN_USE: AtomicBool = AtomicBool::new(false);
+static MENU_EVENT_HANDLER: RwLock<Option<MenuEventHandler>> = RwLock::new(None);
+/* Subscribers keyed by the handle of the main Menu */
+type Subscribers = HashMap<isize, Vec<(u32, Subscriber)>>;
+static MENU_SUBSCRIBERS: LazyLock<Mutex<Subscribers>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static SUBSCRIPTION_ID: AtomicU32 = AtomicU32::new(0);
 
 impl MenuEvent {
-    pub fn item(&self) -> &MenuItem {
-        &self.item
+    /// MenuItem of the event if any.
+    pub fn item(&self) -> Option<&MenuItem> {
+        match self {
+            MenuEvent::Selected {
+                item,
+                ..
+            }
+            | MenuEvent::Toggled {
+                item,
+                ..
+            } => Some(item),
+            MenuEvent::Highlighted(item) | MenuEvent::SubmenuOpened(item) | MenuEvent::SubmenuClosed(item) => Some(item),
+            MenuEvent::Opened
+            | MenuEvent::Closed {
+                ..
+            } => None,
+        }
     }
 
+    /// Receiver of events from all Menus. Not used while an event handler is set.
     pub fn receiver<'a>() -> &'a MenuEventReceiver {
+        MENU_CHANNEL_IN_USE.store(true, Ordering::Relaxed);
         &MENU_CHANNEL.1
     }
 
-    fn innner_receiver<'a>() -> &'a InnerMenuEventReceiver {
-        &INNER_MENU_CHANNEL.1
+    /// Stream of events from all Menus. Shares events with MenuEvent::receiver(), so each event is yielded by only one of them.
+    pub fn stream() -> MenuEventStream {
+        MenuEventStream::new(MenuEvent::receiver().clone())
     }
 
+    /// Sets the handler of events from all Menus, replacing the current one. None removes the handler.
     pub fn set_event_handler<F: Fn(MenuEvent) + Send + Sync + 'static>(f: Option<F>) {
-        if let Some(f) = f {
-            let _ = MENU_EVENT_HANDLER.set(Some(std::boxed::Box::new(f)));
-        } else {
-            let _ = MENU_EVENT_HANDLER.set(None);
-        }
+        let handler = f.map(|f| Arc::new(f) as MenuEventHandler);
+        *MENU_EVENT_HANDLER.write().unwrap() = handler;
     }
 
-    fn send(event: MenuEvent) {
-        if let Some(handler) = MENU_EVENT_HANDLER.get_or_init(|| None) {
+    /// Sends the event to the subscribers of the main Menu and to the global handler or channel.
+    fn send(menu_handle: isize, event: MenuEvent) {
+        /* Clone subscribers so that handlers can subscribe or unsubscribe */
+        let subscribers = MENU_SUBSCRIBERS.lock().unwrap().get(&menu_handle).cloned().unwrap_or_default();
+        let mut closed_ids = Vec::new();
+        for (id, subscriber) in subscribers {
+            match subscriber {
+                Subscriber::Handler(handler) => handler(event.clone()),
+                Subscriber::Channel(sender) => {
+                    if sender.try_send(event.clone()).is_err() {
+                        closed_ids.push(id);
+                    }
+                }
+                #[cfg(feature = "tokio")]
+                Subscriber::Tokio(sender) => {
+                    if sender.send(event.clone()).is_err() {
+                        closed_ids.push(id);
+                    }
+                }
+            }
+        }
+
+        /* Remove receivers already dropped */
+        if !closed_ids.is_empty() {
+            if let Some(subscribers) = MENU_SUBSCRIBERS.lock().unwrap().get_mut(&menu_handle) {
+                subscribers.retain(|(id, _)| !closed_ids.contains(id));
+            }
+        }
+
+        let handler = MENU_EVENT_HANDLER.read().unwrap().clone();
+        if let Some(handler) = handler {
             handler(event);
-        } else {
+        } else if matches!(event, MenuEvent::Selected { .. }) || MENU_CHANNEL_IN_USE.load(Ordering::Relaxed) {
             let _ = MENU_CHANNEL.0.send_blocking(event);
         }
     }
+}
+
+/// Registration of a handler or receiver created by [`Menu::subscribe`] or [`Menu::receiver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Subscription {
+    menu_handle: isize,
+    id: u32,
+}
+
+impl Subscription {
+    /// Stops delivering events to the handler or receiver.
+    pub fn unsubscribe(self) {
+        if let Some(subscribers) = MENU_SUBSCRIBERS.lock().unwrap().get_mut(&self.menu_handle) {
+            subscribers.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+impl Menu {
+    /// Adds a handler of events only from this Menu and its submenus.
+    /// Several handlers can be added. Call [`Subscription::unsubscribe`] to remove it.
+    pub fn subscribe<F: Fn(MenuEvent) + Send + Sync + 'static>(&self, f: F) -> Subscription {
+        add_subscriber(self.main_menu_handle(), Subscriber::Handler(Arc::new(f)))
+    }
+
+    /// Creates a receiver of events only from this Menu and its submenus.
+    /// The receiver is unsubscribed when it is dropped.
+    pub fn receiver(&self) -> MenuEventReceiver {
+        let (sender, receiver) = unbounded();
+        add_subscriber(self.main_menu_handle(), Subscriber::Channel(sender));
+        receiver
+    }
+
+    /// Creates a Stream of events only from this Menu and its submenus.
+    /// The Stream is unsubscribed when it is dropped.
+    pub fn stream(&self) -> MenuEventStream {
+        MenuEventStream::new(self.receiver())
+    }
+
+    /// Creates a tokio receiver of events only from this Menu and its submenus.
+    /// The receiver is unsubscribed when it is dropped.
+    #[cfg(feature = "tokio")]
+    pub fn tokio_receiver(&self) -> tokio::sync::mpsc::UnboundedReceiver<MenuEvent> {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        add_subscriber(self.main_menu_handle(), Subscriber::Tokio(sender));
+        receiver
+    }
+}
+
+/// Stream of MenuEvent that works on any async runtime.
+#[derive(Debug)]
+pub struct MenuEventStream {
+    receiver: Pin<Box<MenuEventReceiver>>,
+}
+
+impl MenuEventStream {
+    fn new(receiver: MenuEventReceiver) -> Self {
+        Self {
+            receiver: Box::pin(receiver),
+        }
+    }
+}
+
+impl Stream for MenuEventStream {
+    type Item = MenuEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.as_mut().poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.receiver.size_hint()
+    }
+}
 
-    fn send_inner(event: InnerMenuEvent) {
-        let _ = INNER_MENU_CHANNEL.0.send_blocking(event);
+fn add_subscriber(menu_handle: isize, subscriber: Subscriber) -> Subscription {
+    let id = SUBSCRIPTION_ID.fetch_add(1, Ordering::Relaxed);
+    MENU_SUBSCRIBERS.lock().unwrap().entry(menu_handle).or_default().push((id, subscriber));
+    Subscription {
+        menu_handle,
+        id,
     }
 }
+
+/// Creates a channel to complete a single popup.
+pub(crate) fn popup_channel() -> (PopupSender, Receiver<PopupResult>) {
+    bounded(1)
+}
+
+/// Removes all subscribers of the main Menu when it is destroyed.
+#[cfg(not(feature = "mock"))]
+pub(crate) fn remove_subscribers(menu_handle: isize) {
+    MENU_SUBSCRIBERS.lock().unwrap().remove(&menu_handle);
+}
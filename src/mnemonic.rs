@@ -0,0 +1,152 @@
+/// Marker of the mnemonic in a label, "_Open" on Linux and "&Open" on Windows as each platform does.
+/// A doubled marker such as "__" or "&&" is shown literally.
+#[cfg(not(target_os = "windows"))]
+pub(crate) const MNEMONIC_MARKER: char = '_';
+#[cfg(target_os = "windows")]
+pub(crate) const MNEMONIC_MARKER: char = '&';
+
+/// Label split into the text to display and its mnemonic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Mnemonic {
+    pub(crate) text: String,
+    /// Char index of the mnemonic in text.
+    pub(crate) index: Option<usize>,
+}
+
+impl Mnemonic {
+    /// Lowercase key of the mnemonic.
+    pub(crate) fn key(&self) -> Option<char> {
+        self.index.and_then(|index| self.text.chars().nth(index)).and_then(|c| c.to_lowercase().next())
+    }
+
+    /// Label for Gtk with use_underline where "_" is the only marker.
+    #[cfg(all(target_os = "linux", not(feature = "mock")))]
+    pub(crate) fn to_underline_label(&self) -> String {
+        let mut label = String::new();
+        for (index, c) in self.text.chars().enumerate() {
+            if self.index == Some(index) {
+                label.push('_');
+            }
+            if c == '_' {
+                label.push('_');
+            }
+            label.push(c);
+        }
+        label
+    }
+}
+
+/// Parses the mnemonic of the label. Only the first marker followed by an alphanumeric character is the mnemonic.
+/// The label is kept as it is unless mnemonics are enabled by Config.mnemonic.
+pub(crate) fn parse_mnemonic(label: &str, enabled: bool) -> Mnemonic {
+    if !enabled {
+        return Mnemonic {
+            text: label.to_string(),
+            index: None,
+        };
+    }
+
+    let mut text = String::new();
+    let mut index = None;
+    let mut count = 0;
+    let mut chars = label.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == MNEMONIC_MARKER {
+            match chars.peek() {
+                Some(&next) if next == c => {
+                    chars.next();
+                }
+                Some(next) if index.is_none() && next.is_alphanumeric() => {
+                    index = Some(count);
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        text.push(c);
+        count += 1;
+    }
+
+    Mnemonic {
+        text,
+        index,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replaces "^" in the template with the marker of the platform.
+    fn label(template: &str) -> String {
+        template.replace('^', &MNEMONIC_MARKER.to_string())
+    }
+
+    fn parse(template: &str) -> Mnemonic {
+        parse_mnemonic(&label(template), true)
+    }
+
+    #[test]
+    fn parses_marker_before_letter() {
+        let mnemonic = parse("^Open");
+        assert_eq!(mnemonic.text, "Open");
+        assert_eq!(mnemonic.index, Some(0));
+        assert_eq!(mnemonic.key(), Some('o'));
+
+        let mnemonic = parse("Save ^As");
+        assert_eq!(mnemonic.text, "Save As");
+        assert_eq!(mnemonic.index, Some(5));
+        assert_eq!(mnemonic.key(), Some('a'));
+
+        assert_eq!(parse("Item ^1").key(), Some('1'));
+        assert_eq!(parse("^Übersicht").key(), Some('ü'));
+    }
+
+    #[test]
+    fn doubled_marker_is_shown_literally() {
+        let mnemonic = parse("^^Escaped");
+        assert_eq!(mnemonic.text, label("^Escaped"));
+        assert_eq!(mnemonic.index, None);
+
+        let mnemonic = parse("a^^b ^c");
+        assert_eq!(mnemonic.text, label("a^b c"));
+        assert_eq!(mnemonic.key(), Some('c'));
+    }
+
+    #[test]
+    fn marker_without_letter_is_shown_literally() {
+        let table = ["End^", "^ space", "^-dash", "^"];
+
+        for template in table {
+            let mnemonic = parse(template);
+            assert_eq!(mnemonic.text, label(template));
+            assert_eq!(mnemonic.index, None, "{}", template);
+        }
+    }
+
+    #[test]
+    fn only_first_marker_is_mnemonic() {
+        let mnemonic = parse("^File ^Edit");
+        assert_eq!(mnemonic.text, label("File ^Edit"));
+        assert_eq!(mnemonic.key(), Some('f'));
+    }
+
+    #[test]
+    fn disabled_keeps_label_as_written() {
+        for template in ["^Open", "^^Escaped", "End^"] {
+            let mnemonic = parse_mnemonic(&label(template), false);
+            assert_eq!(mnemonic.text, label(template));
+            assert_eq!(mnemonic.index, None);
+        }
+    }
+
+    #[cfg(all(target_os = "linux", not(feature = "mock")))]
+    #[test]
+    fn underline_label_escapes_literal_underscores() {
+        let mnemonic = parse("snake__case _Open");
+        assert_eq!(mnemonic.text, "snake_case Open");
+        assert_eq!(mnemonic.to_underline_label(), "snake__case _Open");
+    }
+}
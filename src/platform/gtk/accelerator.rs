@@ -1,19 +1,24 @@
 use super::to_gtk_menu_item;
-#[cfg(feature = "accelerator")]
 use super::to_gtk_window;
+#[cfg(feature = "accelerator")]
+use super::util::{get_menu_data, get_menu_data_mut, get_menu_item_data, menu_item_children, to_gtk_menu};
 use super::{util::get_accel_group, MenuItem, MenuItemType};
 #[cfg(feature = "accelerator")]
+use crate::{accelerator::ChordStep, ActivationSource, PopupResult};
+use crate::{config::AcceleratorConflictPolicy, validation::report_accelerator_conflict, Accelerator, AcceleratorChord, AcceleratorConflict, Error};
 use gtk::{
-    accelerator_name,
-    glib::{Propagation, Quark},
-    prelude::AccelGroupExt,
-};
-use gtk::{
+    accel_groups_from_object,
     gdk::{keys::Key, ModifierType},
-    prelude::WidgetExt,
+    prelude::{AccelGroupExt, WidgetExt},
     traits::GtkWindowExt,
     AccelFlags, AccelGroup,
 };
+#[cfg(feature = "accelerator")]
+use gtk::{
+    accelerator_name,
+    gdk::EventKey,
+    glib::{Cast, ObjectExt, Propagation, Quark},
+};
 use std::collections::HashMap;
 
 pub(crate) struct AcceleratorKey {
@@ -21,8 +26,6 @@ pub(crate) struct AcceleratorKey {
     pub(crate) modifier_type: ModifierType,
 }
 
-const MODIFIERS: [&str; 3] = ["CTRL", "ALT", "SHIFT"];
-
 pub(crate) fn setup_accel_group(accelerators: &HashMap<isize, String>) -> AccelGroup {
     let accel_group = AccelGroup::new();
     for (menu_item_handle, accelerator) in accelerators {
@@ -73,35 +76,107 @@ pub(crate) fn add_accelerators_from_menu_item(main_gtk_menu_handle: isize, item:
 }
 
 pub(crate) fn get_accelerator_key(accelerator: &str) -> Option<AcceleratorKey> {
-    let upper_key = accelerator.to_uppercase();
-    let upper_keys: Vec<&str> = upper_key.split('+').collect();
+    /* Invalid accelerator is reported by MenuBuilder::build */
+    let accelerator: Accelerator = accelerator.parse().ok()?;
+    let key = Key::from_name(to_gdk_key_name(accelerator.key()).as_str());
 
-    if MODIFIERS.contains(&upper_keys[upper_keys.len() - 1]) {
-        return None;
-    }
+    let modifiers = accelerator.modifiers();
+    let mut modifier_type = ModifierType::empty();
+    modifier_type.set(ModifierType::CONTROL_MASK, modifiers.ctrl);
+    modifier_type.set(ModifierType::MOD1_MASK, modifiers.alt);
+    modifier_type.set(ModifierType::SHIFT_MASK, modifiers.shift);
+    modifier_type.set(ModifierType::SUPER_MASK, modifiers.super_key);
 
-    let keys: Vec<&str> = accelerator.split('+').collect();
-    let key_str = keys[keys.len() - 1];
-    let key = Key::from_name(key_str);
+    Some(AcceleratorKey {
+        key: *key,
+        modifier_type,
+    })
+}
 
-    let mut modifier_type = ModifierType::empty();
+fn to_gdk_key_name(key: &str) -> String {
+    let name = match key {
+        "Plus" => "plus",
+        "Equal" => "equal",
+        "Space" => "space",
+        "CapsLock" => "Caps_Lock",
+        "NumLock" => "Num_Lock",
+        "ScrollLock" => "Scroll_Lock",
+        "Backspace" => "BackSpace",
+        "Enter" => "Return",
+        "ArrowLeft" => "Left",
+        "ArrowUp" => "Up",
+        "ArrowRight" => "Right",
+        "ArrowDown" => "Down",
+        "PageUp" => "Page_Up",
+        "PageDown" => "Page_Down",
+        "BrowserHome" => "HomePage",
+        "AudioVolumeMute" => "AudioMute",
+        "AudioVolumeDown" => "AudioLowerVolume",
+        "AudioVolumeUp" => "AudioRaiseVolume",
+        "MediaTrackNext" => "AudioNext",
+        "MediaTrackPrevious" => "AudioPrev",
+        "MediaStop" => "AudioStop",
+        "MediaPlayPause" => "AudioPlay",
+        "PrintScreen" => "Print",
+        "Comma" => "comma",
+        "Minus" => "minus",
+        "Period" => "period",
+        "Semicolon" => "semicolon",
+        "Slash" => "slash",
+        "Backquote" => "grave",
+        "BracketLeft" => "bracketleft",
+        "Backslash" => "backslash",
+        "BracketRight" => "bracketright",
+        "Quote" => "apostrophe",
+        "KanaMode" => "Kana_Lock",
+        "NonConvert" => "Muhenkan",
+        "ContextMenu" => "Menu",
+        "Convert" => "Henkan",
+        /* Letters are registered in lower case as Gtk does */
+        _ if key.len() == 1 => return key.to_lowercase(),
+        _ => key,
+    };
+    name.to_string()
+}
 
-    if upper_keys.contains(&"CTRL") {
-        modifier_type.set(ModifierType::CONTROL_MASK, true);
+/// Checks the accelerators against the accel groups of the window except the Menu's own one.
+pub(crate) fn validate_window_accelerators(
+    gtk_window_handle: isize,
+    own_accel_group: Option<&AccelGroup>,
+    accelerators: &HashMap<AcceleratorChord, String>,
+    policy: AcceleratorConflictPolicy,
+    warnings: &mut Vec<AcceleratorConflict>,
+) -> Result<(), Error> {
+    if policy == AcceleratorConflictPolicy::Allow || accelerators.is_empty() {
+        return Ok(());
     }
 
-    if upper_keys.contains(&"ALT") {
-        modifier_type.set(ModifierType::MOD1_MASK, true);
-    }
+    let gtk_window = to_gtk_window(gtk_window_handle);
+    let accel_groups: Vec<AccelGroup> = accel_groups_from_object(&gtk_window).into_iter().filter(|accel_group| Some(accel_group) != own_accel_group).collect();
+
+    /* Chords are not registered to accel groups */
+    for (accelerator, id) in accelerators.iter().filter(|(chord, _)| !chord.is_sequence()) {
+        let Some(accelerator_key) = get_accelerator_key(&accelerator.to_string()) else {
+            continue;
+        };
+
+        let used_by_window =
+            accel_groups.iter().any(|accel_group| accel_group.find(|accel_key, _| accel_key.accel_key() == accelerator_key.key && accel_key.accel_mods() == accelerator_key.modifier_type).is_some());
 
-    if upper_keys.contains(&"SHIFT") {
-        modifier_type.set(ModifierType::SHIFT_MASK, true);
+        if used_by_window {
+            report_accelerator_conflict(
+                AcceleratorConflict {
+                    accelerator: accelerator.to_string(),
+                    id: id.clone(),
+                    other_id: String::new(),
+                },
+                policy,
+                warnings,
+            )?;
+        }
     }
 
-    Some(AcceleratorKey {
-        key: *key,
-        modifier_type,
-    })
+    Ok(())
 }
 
 pub(crate) fn add_accel_group(gtk_window: &gtk::Window, gtk_menu_handle: isize) {
@@ -122,13 +197,34 @@ pub(crate) fn connect_accelerator(gtk_menu: &gtk::Menu, gtk_menu_handle: isize,
 
         modifiers &= !(ModifierType::MOD2_MASK | ModifierType::MOD3_MASK | ModifierType::MOD4_MASK | ModifierType::MOD5_MASK);
 
+        /* Chords are handled here as accel group accepts only a single key */
+        let mut chords = Vec::new();
+        collect_chords(gtk_menu_handle, &mut chords);
+        chords.retain(|(chord, _)| chord.is_sequence());
+        let step = get_menu_data_mut(gtk_menu_handle).chord_state.press(|accelerator| is_accelerator_pressed(accelerator, event), chords);
+        match step {
+            ChordStep::Matched(gtk_menu_item) => {
+                get_menu_data_mut(gtk_menu_handle).activation_source = Some(ActivationSource::Accelerator);
+                gtk_menu_item.activate();
+                get_menu_data_mut(gtk_menu_handle).activation_source = None;
+                report_as_accelerator(gtk_menu_handle);
+                memu.hide();
+                return Propagation::Stop;
+            }
+            ChordStep::Pending | ChordStep::Cancelled => return Propagation::Stop,
+            ChordStep::Unmatched => {}
+        }
+
         if let Some(quark) = accelerator_name(*key_val, modifiers) {
             let accel_quark = Quark::from_str(quark);
             let accel_group = get_accel_group(gtk_menu_handle);
             let gtk_window = to_gtk_window(gtk_window_handle);
+            get_menu_data_mut(gtk_menu_handle).activation_source = Some(ActivationSource::Accelerator);
             let result = accel_group.activate(accel_quark, &gtk_window, *key_val, modifiers);
+            get_menu_data_mut(gtk_menu_handle).activation_source = None;
 
             if result {
+                report_as_accelerator(gtk_menu_handle);
                 memu.hide();
                 return Propagation::Stop;
             } else {
@@ -139,3 +235,71 @@ pub(crate) fn connect_accelerator(gtk_menu: &gtk::Menu, gtk_menu_handle: isize,
         Propagation::Proceed
     });
 }
+
+/// Item activated by accelerator is reported as Accelerator.
+#[cfg(feature = "accelerator")]
+fn report_as_accelerator(gtk_menu_handle: isize) {
+    let menu_data = get_menu_data_mut(gtk_menu_handle);
+    if let Some(PopupResult::Selected(item)) = menu_data.result.take() {
+        menu_data.result = Some(PopupResult::Accelerator(item));
+    }
+}
+
+/// Activates the MenuItem of the pressed accelerator on the window while Menu is closed and window shortcuts are enabled.
+#[cfg(feature = "accelerator")]
+pub(crate) fn connect_window_shortcuts(gtk_window: &gtk::Window, gtk_menu_handle: isize) {
+    let weak_gtk_menu = to_gtk_menu(gtk_menu_handle).downgrade();
+    gtk_window.connect_key_press_event(move |_, event| {
+        if weak_gtk_menu.upgrade().is_none() {
+            return Propagation::Proceed;
+        }
+
+        /* Accelerators are handled by the accel group while Menu is shown */
+        let menu_data = get_menu_data(gtk_menu_handle);
+        if !menu_data.window_shortcuts || menu_data.visible {
+            return Propagation::Proceed;
+        }
+
+        let mut chords = Vec::new();
+        collect_chords(gtk_menu_handle, &mut chords);
+        let step = get_menu_data_mut(gtk_menu_handle).chord_state.press(|accelerator| is_accelerator_pressed(accelerator, event), chords);
+        match step {
+            ChordStep::Matched(gtk_menu_item) => {
+                get_menu_data_mut(gtk_menu_handle).activation_source = Some(ActivationSource::Shortcut);
+                gtk_menu_item.activate();
+                get_menu_data_mut(gtk_menu_handle).activation_source = None;
+                Propagation::Stop
+            }
+            ChordStep::Pending | ChordStep::Cancelled => Propagation::Stop,
+            ChordStep::Unmatched => Propagation::Proceed,
+        }
+    });
+}
+
+#[cfg(feature = "accelerator")]
+fn is_accelerator_pressed(accelerator: &Accelerator, event: &EventKey) -> bool {
+    let key = *event.keyval().to_lower();
+    let modifier_type = event.state() & (ModifierType::CONTROL_MASK | ModifierType::MOD1_MASK | ModifierType::SHIFT_MASK | ModifierType::SUPER_MASK);
+    get_accelerator_key(&accelerator.to_string()).is_some_and(|accelerator_key| accelerator_key.key == key && accelerator_key.modifier_type == modifier_type)
+}
+
+/// Collects the accelerators of visible and enabled MenuItems including submenus.
+#[cfg(feature = "accelerator")]
+fn collect_chords(gtk_menu_handle: isize, chords: &mut Vec<(AcceleratorChord, gtk::MenuItem)>) {
+    for gtk_menu_item in menu_item_children(&to_gtk_menu(gtk_menu_handle)) {
+        let item = get_menu_item_data(&gtk_menu_item);
+        if !item.visible || item.disabled {
+            continue;
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            if let Some(submenu) = item.submenu.as_ref() {
+                collect_chords(submenu.gtk_menu_handle, chords);
+            }
+        } else if let Ok(chord) = item.accelerator.parse::<AcceleratorChord>() {
+            if let Ok(gtk_menu_item) = gtk_menu_item.downcast::<gtk::MenuItem>() {
+                chords.push((chord, gtk_menu_item));
+            }
+        }
+    }
+}
@@ -1,22 +1,48 @@
-use super::{accelerator::setup_accel_group, create_gtk_menu_item, from_gtk_menu, to_gtk_menu, to_gtk_window, toggle_menu_item_icons, Container};
+#[cfg(feature = "accelerator")]
+use super::accelerator::{connect_accelerator, connect_window_shortcuts};
+use super::{
+    accelerator::{setup_accel_group, validate_window_accelerators},
+    connect_menu_events, create_gtk_menu_item, from_gtk_menu,
+    search::{add_search_field, SearchState},
+    theme::watch_system_theme,
+    to_gtk_menu, to_gtk_window, toggle_menu_item_icons,
+    type_ahead::TypeAheadState,
+    Container,
+};
+#[cfg(feature = "accelerator")]
+use crate::accelerator::ChordState;
 use crate::{
-    config::{Config, IconSettings, Theme},
-    Menu, MenuIcon, MenuIconKind, MenuItem, MenuItemType, MenuType,
+    config::{Config, IconSettings, SearchSettings, Theme},
+    remove_subscribers,
+    validation::{validate_accelerator_conflicts, validate_config, validate_items},
+    AcceleratorConflict, ActivationSource, Error, Menu, MenuIcon, MenuIconKind, MenuItem, MenuItemType, MenuType, PopupResult, PopupSender,
 };
 use gtk::{
-    glib::{Error, IsA, ObjectExt},
+    glib::{IsA, ObjectExt},
     prelude::MenuShellExt,
     traits::{ContainerExt, WidgetExt},
     AccelGroup,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone)]
 pub(crate) struct MenuData {
     pub(crate) config: Config,
     pub(crate) visible: bool,
+    pub(crate) result: Option<PopupResult>,
+    pub(crate) popup_id: u32,
+    pub(crate) popup_sender: Option<PopupSender>,
     pub(crate) parent_gtk_menu_handle: isize,
     pub(crate) has_custom_check_image: bool,
+    pub(crate) window_shortcuts: bool,
+    /* Conflicts found under AcceleratorConflictPolicy::Warn, kept in the main menu */
+    pub(crate) accelerator_conflicts: Vec<AcceleratorConflict>,
+    /* Set while a MenuItem is activated by other than the shown Menu */
+    pub(crate) activation_source: Option<ActivationSource>,
+    #[cfg(feature = "accelerator")]
+    pub(crate) chord_state: ChordState,
+    pub(crate) type_ahead: TypeAheadState,
+    pub(crate) search: SearchState,
 }
 
 #[derive(Debug)]
@@ -130,8 +156,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn text_with_accelerator(&mut self, id: &str, label: &str, disabled: bool, accelerator: &str) -> &Self {
-        let mut item = MenuItem::new_text_item(id, label, Some(accelerator), disabled, None);
+    pub fn text_with_accelerator(&mut self, id: &str, label: &str, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_text_item(id, label, Some(accelerator.as_str()), disabled, None);
         self.create_item(&mut item);
         self.items.push(item);
         self
@@ -144,8 +171,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn text_with_accel_icon(&mut self, id: &str, label: &str, disabled: bool, accelerator: &str, icon: MenuIcon) -> &Self {
-        let mut item = MenuItem::new_text_item(id, label, Some(accelerator), disabled, Some(icon));
+    pub fn text_with_accel_icon(&mut self, id: &str, label: &str, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_text_item(id, label, Some(accelerator.as_str()), disabled, Some(icon));
         self.create_item(&mut item);
         self.items.push(item);
         self
@@ -159,8 +187,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn check_with_accelerator(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: &str) -> &Self {
-        let mut item = MenuItem::new_check_item(id, label, Some(accelerator), checked, disabled, None);
+    pub fn check_with_accelerator(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_check_item(id, label, Some(accelerator.as_str()), checked, disabled, None);
         self.create_item(&mut item);
         self.items.push(item);
         self
@@ -173,8 +202,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn check_with_accel_icon(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: &str, icon: MenuIcon) -> &Self {
-        let mut item = MenuItem::new_check_item(id, label, Some(accelerator), checked, disabled, Some(icon));
+    pub fn check_with_accel_icon(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_check_item(id, label, Some(accelerator.as_str()), checked, disabled, Some(icon));
         self.create_item(&mut item);
         self.items.push(item);
         self
@@ -188,8 +218,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn radio_with_accelerator(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: &str) -> &Self {
-        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator), checked, disabled, None);
+    pub fn radio_with_accelerator(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator.as_str()), checked, disabled, None);
         self.create_item(&mut item);
         self.items.push(item);
         self
@@ -203,8 +234,9 @@ impl MenuBuilder {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn radio_with_accel_icon(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: &str, icon: MenuIcon) -> &Self {
-        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator), checked, disabled, Some(icon));
+    pub fn radio_with_accel_icon(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator.as_str()), checked, disabled, Some(icon));
         self.create_item(&mut item);
         self.items.push(item);
         self
@@ -225,6 +257,21 @@ impl MenuBuilder {
         self
     }
 
+    /// Adds a section heading to Menu. It cannot be selected.
+    pub fn header(&mut self, label: &str) -> &Self {
+        let mut item = MenuItem::new_header(label);
+        self.create_item(&mut item);
+        self.items.push(item);
+        self
+    }
+
+    pub fn header_with_id(&mut self, id: &str, label: &str) -> &Self {
+        let mut item = MenuItem::new_header_with_id(id, label);
+        self.create_item(&mut item);
+        self.items.push(item);
+        self
+    }
+
     fn create_item(&mut self, item: &mut MenuItem) {
         let gtk_menu_item = create_gtk_menu_item(self.menu.gtk_menu_handle, item, None, Some(&mut self.radio_groups), &self.config);
         self.gtk_menu.append(&gtk_menu_item);
@@ -308,7 +355,7 @@ impl MenuBuilder {
             gtk_menu,
             radio_groups,
         };
-        /* Safe to unwrap, because Result is for compatibility with Windows */
+        /* Safe to unwrap, because submenus are validated as part of the main menu */
         builder.build().unwrap();
 
         gtk_submenu_item
@@ -334,6 +381,12 @@ impl MenuBuilder {
         self
     }
 
+    /// Adds a search field at the top of Menu to find MenuItems in Menu and its submenus. Only for the main Menu.
+    pub fn search(&mut self, settings: SearchSettings) -> &Self {
+        self.config.search = Some(settings);
+        self
+    }
+
     /// Build Menu to make it ready to become visible.
     /// Must call this function before showing Menu, otherwise nothing shows up.
     pub fn build(self) -> Result<Menu, Error> {
@@ -341,6 +394,18 @@ impl MenuBuilder {
 
         let is_main_menu = self.menu.menu_type == MenuType::Main;
 
+        /* Submenus are validated as part of the main menu */
+        let mut warnings = Vec::new();
+        if is_main_menu {
+            validate_config(&self.config)?;
+            validate_items(&self.items, &mut HashSet::new())?;
+
+            let policy = self.config.accelerator_conflict;
+            let mut used_accelerators = HashMap::new();
+            validate_accelerator_conflicts(&self.items, &mut used_accelerators, policy, &mut warnings)?;
+            validate_window_accelerators(self.menu.gtk_window_handle, None, &used_accelerators, policy, &mut warnings)?;
+        }
+
         if !is_main_menu && gtk_menu.children().is_empty() {
             gtk_menu.set_sensitive(false);
         }
@@ -371,14 +436,39 @@ impl MenuBuilder {
         let data = MenuData {
             config: self.config,
             visible: false,
+            result: None,
+            popup_id: 0,
+            popup_sender: None,
             parent_gtk_menu_handle: self.menu.parent_gtk_menu_handle,
             has_custom_check_image,
+            window_shortcuts: false,
+            accelerator_conflicts: warnings,
+            activation_source: None,
+            #[cfg(feature = "accelerator")]
+            chord_state: ChordState::default(),
+            type_ahead: TypeAheadState::default(),
+            search: SearchState::default(),
         };
 
         unsafe { gtk_menu.set_data("data", data) };
 
         toggle_menu_item_icons(self.menu.gtk_menu_handle);
 
+        connect_menu_events(&gtk_menu, &self.menu);
+
+        if is_main_menu {
+            add_search_field(&gtk_menu, self.menu.gtk_menu_handle);
+            #[cfg(feature = "accelerator")]
+            {
+                /* Connect once, the handler reads the current accel group and chords on each key press */
+                connect_accelerator(&gtk_menu, self.menu.gtk_menu_handle, self.menu.gtk_window_handle);
+                connect_window_shortcuts(&to_gtk_window(self.menu.gtk_window_handle), self.menu.gtk_menu_handle);
+            }
+            watch_system_theme(self.menu.gtk_menu_handle);
+            let gtk_menu_handle = self.menu.gtk_menu_handle;
+            gtk_menu.connect_destroy(move |_| remove_subscribers(gtk_menu_handle));
+        }
+
         Ok(self.menu)
     }
 }
@@ -1,13 +1,16 @@
 use super::{
-    collect_menu_items, from_gtk_menu_item, get_menu_data, get_menu_item_data_mut, get_path_icon_css,
-    style::{get_data_icon_css, get_hidden_image_css, get_menu_item_css, get_svg_icon_css, get_widget_name, CUSTOM_CHECKMARK_NAME},
+    collect_menu_items, from_gtk_menu_item, get_menu_data, get_menu_data_mut, get_menu_item_data_mut, get_path_icon_css,
+    style::{get_data_icon_css, get_hidden_image_css, get_menu_item_css, get_svg_icon_css, get_widget_name, CUSTOM_CHECKMARK_NAME, DESCRIPTION_CLASS, HEADER_CLASS},
     to_gtk_menu_item,
-    util::{get_menu_item_data, is_check_menu_item, is_sys_dark, to_gtk_menu},
+    util::{get_main_menu_handle, get_menu_item_data, is_check_menu_item, is_sys_dark, to_gtk_menu},
     Menu, MenuData, SubmenuData,
 };
 use crate::{
-    config::{to_hex_string, Config, Theme},
-    InnerMenuEvent, MenuEvent, MenuIcon, MenuIconKind, MenuItemType, SvgIcon,
+    accelerator::display_accelerator,
+    config::{to_hex_string, AcceleratorFormat, Config, Theme},
+    mnemonic::parse_mnemonic,
+    validation::validate_icon,
+    AcceleratorChord, ActivationSource, CheckState, Error, MenuEvent, MenuIcon, MenuIconKind, MenuItemType, PopupResult, SvgIcon,
 };
 use gtk::{
     cairo::{Format, ImageSurface},
@@ -16,9 +19,9 @@ use gtk::{
     gdk_pixbuf::{Colorspace, Pixbuf},
     gio::{Cancellable, MemoryInputStream},
     glib::{translate::ToGlibPtr, Cast, IsA, ObjectExt},
-    prelude::{AccelLabelExt, BoxExt, CheckMenuItemExt, ContainerExt, CssProviderExt, GtkMenuItemExt, RadioMenuItemExt, StyleContextExt, WidgetExt},
+    prelude::{AccelLabelExt, BoxExt, CheckMenuItemExt, ContainerExt, CssProviderExt, GtkMenuItemExt, LabelExt, OrientableExt, RadioMenuItemExt, StyleContextExt, WidgetExt},
     traits::ImageExt,
-    AccelLabel, CssProvider, Orientation, StyleProvider, Widget, STYLE_PROVIDER_PRIORITY_APPLICATION,
+    AccelLabel, CssProvider, Label, Orientation, StyleProvider, Widget, STYLE_PROVIDER_PRIORITY_APPLICATION,
 };
 use serde::{Deserialize, Serialize};
 use std::{
@@ -37,10 +40,21 @@ pub struct MenuItem {
     pub name: String,
     pub menu_item_type: MenuItemType,
     pub submenu: Option<Menu>,
-    pub checked: bool,
+    /// Check state of a checkbox or radio. Mixed is only for checkbox.
+    #[serde(default)]
+    pub check_state: CheckState,
     pub disabled: bool,
     pub visible: bool,
     pub icon: Option<MenuIcon>,
+    /// Extra words matched by search besides the label.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Text shown on hover, also for disabled MenuItems.
+    #[serde(default)]
+    pub tooltip: Option<String>,
+    /// Second line shown under the label in smaller and dimmer text.
+    #[serde(default)]
+    pub description: Option<String>,
     pub uuid: u16,
     pub(crate) gtk_menu_item_handle: isize,
     pub(crate) items: Option<Vec<MenuItem>>,
@@ -60,11 +74,14 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled: false,
             visible: true,
             items: None,
             icon: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
@@ -79,10 +96,59 @@ impl MenuItem {
 
         let gtk_menu_item = to_gtk_menu_item(self.gtk_menu_item_handle);
         let menu_item = get_menu_item_data_mut(&gtk_menu_item);
-        gtk_menu_item.set_label(label);
+        let mnemonic = parse_mnemonic(label, get_menu_data(self.gtk_menu_handle).config.mnemonic);
+        if let Some(accel_label) = get_gtk_label(&gtk_menu_item) {
+            accel_label.set_label(&mnemonic.to_underline_label());
+        } else if let Some(header_label) = gtk_menu_item.children().into_iter().find_map(|child| child.downcast::<Label>().ok()) {
+            header_label.set_label(&mnemonic.text);
+        }
         menu_item.label = label.to_string();
     }
 
+    /// Sets the words matched by search besides the label.
+    pub fn set_keywords(&mut self, keywords: &[&str]) {
+        self.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+
+        /* Exit if window is not created */
+        if self.gtk_menu_item_handle == 0 {
+            return;
+        }
+
+        let gtk_menu_item = to_gtk_menu_item(self.gtk_menu_item_handle);
+        get_menu_item_data_mut(&gtk_menu_item).keywords.clone_from(&self.keywords);
+    }
+
+    /// Sets the second line shown under the label. None removes it.
+    pub fn set_description(&mut self, description: Option<&str>) {
+        self.description = description.map(str::to_string);
+
+        /* Exit if window is not created */
+        if self.gtk_menu_item_handle == 0 {
+            return;
+        }
+
+        let gtk_menu_item = to_gtk_menu_item(self.gtk_menu_item_handle);
+        if let Some(label) = get_gtk_description(&gtk_menu_item) {
+            label.set_label(description.unwrap_or_default());
+            label.set_visible(description.is_some());
+        }
+        get_menu_item_data_mut(&gtk_menu_item).description.clone_from(&self.description);
+    }
+
+    /// Sets the text shown on hover. A disabled MenuItem shows it as well, for example to explain why it is disabled.
+    pub fn set_tooltip(&mut self, tooltip: Option<&str>) {
+        self.tooltip = tooltip.map(str::to_string);
+
+        /* Exit if window is not created */
+        if self.gtk_menu_item_handle == 0 {
+            return;
+        }
+
+        let gtk_menu_item = to_gtk_menu_item(self.gtk_menu_item_handle);
+        gtk_menu_item.set_tooltip_text(tooltip);
+        get_menu_item_data_mut(&gtk_menu_item).tooltip.clone_from(&self.tooltip);
+    }
+
     pub fn set_disabled(&mut self, disabled: bool) {
         self.disabled = disabled;
 
@@ -115,19 +181,29 @@ impl MenuItem {
         toggle_menu_item_icons(self.gtk_menu_handle);
     }
 
-    pub fn set_icon(&mut self, icon: Option<MenuIcon>) {
-        if self.menu_item_type == MenuItemType::Separator {
-            return;
+    pub fn set_icon(&mut self, icon: Option<MenuIcon>) -> Result<(), Error> {
+        if !self.menu_item_type.is_selectable() {
+            return Ok(());
         }
 
-        self.icon = icon;
+        if let Some(icon) = &icon {
+            validate_icon(icon)?;
+        }
 
         /* Exit if window is not created */
         if self.gtk_menu_item_handle == 0 {
-            return;
+            self.icon = icon;
+            return Ok(());
         }
 
         let data = get_menu_data(self.gtk_menu_handle);
+
+        /* Make sure that SVG can be rendered before replacing the current icon */
+        if let Some(MenuIconKind::Svg(svg)) = icon.as_ref().map(|icon| &icon.icon) {
+            get_svg_surface(svg, &data.config, self.disabled)?;
+        }
+
+        self.icon = icon;
         let gtk_menu_item = to_gtk_menu_item(self.gtk_menu_item_handle);
 
         /* If icon is None, remove only */
@@ -140,6 +216,7 @@ impl MenuItem {
         menu_item.icon = self.icon.clone();
 
         toggle_menu_item_icons(self.gtk_menu_handle);
+        Ok(())
     }
 }
 
@@ -155,11 +232,14 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
@@ -177,11 +257,14 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked,
+            check_state: checked.into(),
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
@@ -197,24 +280,36 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked,
+            check_state: checked.into(),
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
 
     pub fn set_checked(&mut self, checked: bool) {
+        self.set_check_state(checked.into());
+    }
+
+    /// Sets the check state. Mixed is only for checkbox and shown in Gtk's inconsistent look unless IconSettings.mixed is set.
+    pub fn set_check_state(&mut self, check_state: CheckState) {
         if !is_check_menu_item(self.menu_item_type) {
             return;
         }
 
-        /* Suppress only when check state changes */
-        let suppress_event = self.checked != checked;
+        if check_state == CheckState::Mixed && self.menu_item_type != MenuItemType::Checkbox {
+            return;
+        }
+
+        /* Suppress only when the active state of gtk::CheckMenuItem changes */
+        let suppress_event = self.check_state.is_checked() != check_state.is_checked();
 
-        self.checked = checked;
+        self.check_state = check_state;
 
         /* Exit if window is not created */
         if self.gtk_menu_item_handle == 0 {
@@ -223,16 +318,23 @@ impl MenuItem {
 
         let gtk_menu_item = to_gtk_menu_item(self.gtk_menu_item_handle);
         let menu_item = get_menu_item_data_mut(&gtk_menu_item);
-        menu_item.checked = checked;
+        let was_mixed = menu_item.check_state == CheckState::Mixed;
+        menu_item.check_state = check_state;
         /* Suppress activate events by check state change */
         menu_item.suppress_event = suppress_event;
 
         if self.menu_item_type == MenuItemType::Checkbox {
-            gtk_menu_item.downcast_ref::<gtk::CheckMenuItem>().unwrap().set_active(checked);
+            let check_menu_item = gtk_menu_item.downcast_ref::<gtk::CheckMenuItem>().unwrap();
+            let mixed = check_state == CheckState::Mixed;
+            if was_mixed != mixed {
+                check_menu_item.set_inconsistent(mixed);
+                update_custom_check_image(&gtk_menu_item, menu_item);
+            }
+            check_menu_item.set_active(check_state.is_checked());
         }
 
         if self.menu_item_type == MenuItemType::Radio {
-            gtk_menu_item.downcast_ref::<gtk::RadioMenuItem>().unwrap().set_active(checked);
+            gtk_menu_item.downcast_ref::<gtk::RadioMenuItem>().unwrap().set_active(check_state.is_checked());
         }
     }
 }
@@ -249,11 +351,14 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled,
             visible: true,
             items: Some(Vec::new()),
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
@@ -279,11 +384,14 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled: false,
             visible: true,
             items: None,
             icon: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
@@ -299,14 +407,30 @@ impl MenuItem {
             uuid: UUID.fetch_add(1, Ordering::Relaxed),
             gtk_menu_item_handle: 0,
             gtk_menu_handle: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled: false,
             visible: true,
             items: None,
             icon: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
             suppress_event: false,
         }
     }
+
+    pub fn new_header(label: &str) -> Self {
+        Self::new_header_with_id("", label)
+    }
+
+    pub fn new_header_with_id(id: &str, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            menu_item_type: MenuItemType::Header,
+            ..Self::new_separator()
+        }
+    }
 }
 
 impl MenuItem {
@@ -332,8 +456,8 @@ impl MenuItemBuilder {
         self
     }
 
-    pub fn accelerator(mut self, accelerator: &str) -> Self {
-        self.menu_item.accelerator = accelerator.to_string();
+    pub fn accelerator(mut self, accelerator: impl Into<String>) -> Self {
+        self.menu_item.accelerator = accelerator.into();
         self
     }
 
@@ -350,7 +474,13 @@ impl MenuItemBuilder {
     }
 
     pub fn checked(mut self, checked: bool) -> Self {
-        self.menu_item.checked = checked;
+        self.menu_item.check_state = checked.into();
+        self
+    }
+
+    /// Check state of a checkbox including the mixed state.
+    pub fn check_state(mut self, check_state: CheckState) -> Self {
+        self.menu_item.check_state = check_state;
         self
     }
 
@@ -369,6 +499,24 @@ impl MenuItemBuilder {
         self
     }
 
+    /// Words matched by search besides the label.
+    pub fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.menu_item.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+        self
+    }
+
+    /// Text shown on hover, also for disabled MenuItems.
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.menu_item.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    /// Second line shown under the label in smaller and dimmer text.
+    pub fn description(mut self, description: &str) -> Self {
+        self.menu_item.description = Some(description.to_string());
+        self
+    }
+
     /// Build the [`MenuItem`].
     pub fn build(self) -> MenuItem {
         self.menu_item
@@ -387,7 +535,7 @@ pub(crate) fn toggle_menu_item_icons(gtk_menu_handle: isize) {
     let has_check = menu_items.iter().any(|item| is_check_menu_item(item.menu_item_type) && item.visible);
 
     for menu_item in menu_items {
-        if menu_item.menu_item_type != MenuItemType::Separator {
+        if menu_item.menu_item_type.is_selectable() {
             let gtk_menu_item = to_gtk_menu_item(menu_item.gtk_menu_item_handle);
             let image = get_gtk_image_for_icon(&gtk_menu_item, data);
 
@@ -441,6 +589,21 @@ fn get_gtk_image_for_toggle(gtk_menu_item: &gtk::MenuItem) -> Widget {
     box_container.children().first().unwrap().clone()
 }
 
+fn get_gtk_text_box(gtk_menu_item: &gtk::MenuItem) -> Option<gtk::Box> {
+    let children = gtk_menu_item.children();
+    let box_container: &gtk::Box = children.first()?.downcast_ref()?;
+    /* Label and description are stacked in the only vertical box */
+    box_container.children().into_iter().filter_map(|child| child.downcast::<gtk::Box>().ok()).find(|text_box| text_box.orientation() == Orientation::Vertical)
+}
+
+fn get_gtk_label(gtk_menu_item: &gtk::MenuItem) -> Option<AccelLabel> {
+    get_gtk_text_box(gtk_menu_item)?.children().into_iter().find_map(|child| child.downcast::<AccelLabel>().ok())
+}
+
+fn get_gtk_description(gtk_menu_item: &gtk::MenuItem) -> Option<Label> {
+    get_gtk_text_box(gtk_menu_item)?.children().into_iter().filter_map(|child| child.downcast::<Label>().ok()).find(|label| label.style_context().has_class(DESCRIPTION_CLASS))
+}
+
 fn get_gtk_image_for_submenu(gtk_menu_item: &gtk::MenuItem) -> Widget {
     let children = gtk_menu_item.children();
     let box_container: &gtk::Box = children.first().unwrap().downcast_ref().unwrap();
@@ -486,10 +649,57 @@ pub(crate) fn apply_theme_to_svg_data(widget: &impl IsA<Widget>, data: &MenuData
     }
 }
 
+/// Returns the Data or Svg icon of the mixed state if the checkbox is mixed and the checkmark is also a Data or Svg icon.
+fn get_custom_check_icon<'a>(item: &MenuItem, config: &'a Config) -> Option<&'a MenuIcon> {
+    let icon_settings = config.icon.as_ref().unwrap();
+    let is_custom_image = |menu_icon: &&MenuIcon| !matches!(menu_icon.icon, MenuIconKind::Path(_));
+    if item.menu_item_type != MenuItemType::Checkbox {
+        return None;
+    }
+
+    let check = icon_settings.check.as_ref().filter(is_custom_image)?;
+    let mixed = icon_settings.mixed.as_ref().filter(is_custom_image);
+    if item.check_state == CheckState::Mixed {
+        mixed
+    } else {
+        mixed.map(|_| check)
+    }
+}
+
+/// Switches the custom checkmark image between the checkmark and the mixed icon.
+fn update_custom_check_image(gtk_menu_item: &gtk::MenuItem, item: &MenuItem) {
+    let config = &get_menu_data(item.gtk_menu_handle).config;
+    let Some(menu_icon) = get_custom_check_icon(item, config) else {
+        return;
+    };
+
+    let image = get_gtk_image_for_toggle(gtk_menu_item).downcast::<gtk::Image>().unwrap();
+    match &menu_icon.icon {
+        MenuIconKind::Data(icon) => {
+            let row_stride = Pixbuf::calculate_rowstride(Colorspace::Rgb, true, 8, icon.width as i32, icon.height as i32);
+            let pixbuf = Pixbuf::from_bytes(&gtk::glib::Bytes::from(&icon.data), Colorspace::Rgb, true, 8, icon.width as i32, icon.height as i32, row_stride);
+            image.set_from_pixbuf(Some(&pixbuf));
+        }
+        MenuIconKind::Svg(svg) => {
+            if let Ok(surface) = get_svg_surface(svg, config, item.disabled) {
+                image.set_from_surface(Some(&surface));
+            }
+        }
+        MenuIconKind::Path(_) => {}
+    }
+}
+
 fn create_icon_label(item: &MenuItem, config: &Config, accel_widget: Option<&impl IsA<Widget>>) -> gtk::Box {
     let box_container = gtk::Box::new(Orientation::Horizontal, 6);
-    let accel_label = AccelLabel::builder().label(&item.label).xalign(0.0).build();
-    accel_label.set_accel_widget(accel_widget);
+    let accel_label = AccelLabel::builder().label(parse_mnemonic(&item.label, config.mnemonic).to_underline_label()).use_underline(true).xalign(0.0).build();
+    /* Mnemonic activates the MenuItem while Menu is shown */
+    accel_label.set_mnemonic_widget(accel_widget);
+    /* AccelLabel shows only a single key in Gtk's format, so show others by another label */
+    let chord = item.accelerator.parse::<AcceleratorChord>().ok();
+    let use_accel_label = config.accelerator_format == AcceleratorFormat::Native && !chord.as_ref().is_some_and(AcceleratorChord::is_sequence);
+    if use_accel_label {
+        accel_label.set_accel_widget(accel_widget);
+    }
     accel_label.show();
 
     /* Initially hide icon */
@@ -507,7 +717,7 @@ fn create_icon_label(item: &MenuItem, config: &Config, accel_widget: Option<&imp
         match check.icon {
             MenuIconKind::Data(_) | MenuIconKind::Svg(_) => {
                 let checked_icon = if is_check_menu_item(item.menu_item_type) {
-                    let image = create_icon(check, config);
+                    let image = create_icon(get_custom_check_icon(item, config).unwrap_or(check), config);
                     /* Set widget name to apply different style */
                     image.set_widget_name(CUSTOM_CHECKMARK_NAME);
                     image
@@ -520,8 +730,26 @@ fn create_icon_label(item: &MenuItem, config: &Config, accel_widget: Option<&imp
         }
     }
 
+    /* Description is always created so that it can be set later, and shown only when it has text */
+    let description = Label::builder().label(item.description.as_deref().unwrap_or_default()).xalign(0.0).build();
+    description.style_context().add_class(DESCRIPTION_CLASS);
+    description.set_visible(item.description.is_some());
+
+    let text_box = gtk::Box::new(Orientation::Vertical, 0);
+    text_box.pack_start(&accel_label, false, false, 0);
+    text_box.pack_start(&description, false, false, 0);
+    text_box.set_valign(gtk::Align::Center);
+    text_box.show();
+
     box_container.pack_start(&image, false, false, 0);
-    box_container.pack_start(&accel_label, true, true, 0);
+    box_container.pack_start(&text_box, true, true, 0);
+
+    if chord.is_some() && !use_accel_label {
+        let chord_label = Label::builder().label(display_accelerator(&item.accelerator, &config.accelerator_format)).xalign(1.0).build();
+        chord_label.style_context().add_class("accelerator");
+        chord_label.show();
+        box_container.pack_start(&chord_label, false, false, 0);
+    }
 
     /* If submenu arrow is overridden and its type is other than Path, add it last and show initially as arrow is always visible */
     if let Some(arrow) = &config.icon.as_ref().unwrap().arrow {
@@ -549,14 +777,16 @@ fn apply_image_css(image: &impl IsA<Widget>, menu_icon: &MenuIcon, config: &Conf
         MenuIconKind::Data(icon) => get_data_icon_css(icon, config),
         MenuIconKind::Svg(icon) => get_svg_icon_css(icon, config),
     };
-    css_provider.load_from_data(css.as_bytes()).unwrap();
+    /* Invalid Config is reported by MenuBuilder::build */
+    let _ = css_provider.load_from_data(css.as_bytes());
     image.style_context().add_provider(&css_provider, STYLE_PROVIDER_PRIORITY_APPLICATION);
 }
 
 fn apply_empty_image_css(image: &impl IsA<Widget>, config: &Config) {
     let css_provider = CssProvider::new();
     let css = get_hidden_image_css(config);
-    css_provider.load_from_data(css.as_bytes()).unwrap();
+    /* Invalid Config is reported by MenuBuilder::build */
+    let _ = css_provider.load_from_data(css.as_bytes());
     image.style_context().add_provider(&css_provider, STYLE_PROVIDER_PRIORITY_APPLICATION);
 }
 
@@ -584,9 +814,9 @@ fn create_icon(menu_icon: &MenuIcon, config: &Config) -> gtk::Image {
     }
 }
 
-fn get_svg_surface(svg: &SvgIcon, config: &Config, disabled: bool) -> Result<ImageSurface, ()> {
+fn get_svg_surface(svg: &SvgIcon, config: &Config, disabled: bool) -> Result<ImageSurface, Error> {
     let stream = MemoryInputStream::from_bytes(&gtk::glib::Bytes::from(svg.data.as_bytes()));
-    let mut handle = rsvg::Loader::new().read_stream(&stream, None::<&gtk::gio::File>, None::<&Cancellable>).map_err(|_| ())?;
+    let mut handle = rsvg::Loader::new().read_stream(&stream, None::<&gtk::gio::File>, None::<&Cancellable>).map_err(|e| Error::InvalidSvg(e.to_string()))?;
     if svg.data.contains("currentColor") {
         let is_dark = match config.theme {
             Theme::Dark => true,
@@ -606,13 +836,13 @@ fn get_svg_surface(svg: &SvgIcon, config: &Config, disabled: bool) -> Result<Ima
                 config.color.light.color
             }
         };
-        handle.set_stylesheet(&format!(r"svg {{color: {}; }}", to_hex_string(color))).map_err(|_| ())?;
+        handle.set_stylesheet(&format!(r"svg {{color: {}; }}", to_hex_string(color))).map_err(|e| Error::InvalidSvg(e.to_string()))?;
     }
     let renderer = rsvg::CairoRenderer::new(&handle);
-    let surface = ImageSurface::create(Format::ARgb32, svg.width as i32, svg.height as i32).map_err(|_| ())?;
-    let context = gtk::cairo::Context::new(&surface).map_err(|_| ())?;
+    let surface = ImageSurface::create(Format::ARgb32, svg.width as i32, svg.height as i32).map_err(|e| Error::InvalidSvg(e.to_string()))?;
+    let context = gtk::cairo::Context::new(&surface).map_err(|e| Error::InvalidSvg(e.to_string()))?;
     let viewport = gtk::cairo::Rectangle::new(0.0, 0.0, f64::from(svg.width), f64::from(svg.height));
-    renderer.render_document(&context, &viewport).map_err(|_| ())?;
+    renderer.render_document(&context, &viewport).map_err(|e| Error::InvalidSvg(e.to_string()))?;
 
     Ok(surface)
 }
@@ -639,7 +869,7 @@ pub(crate) fn create_gtk_menu_item(
             gtk_menu_item
         }
         MenuItemType::Checkbox => {
-            let check_menu_item = gtk::CheckMenuItem::builder().sensitive(!item.disabled).active(item.checked).build();
+            let check_menu_item = gtk::CheckMenuItem::builder().sensitive(!item.disabled).active(item.check_state.is_checked()).inconsistent(item.check_state == CheckState::Mixed).build();
             let gtk_menu_item = check_menu_item.upcast::<gtk::MenuItem>();
             let box_container = create_icon_label(item, config, Some(&gtk_menu_item));
             gtk_menu_item.add(&box_container);
@@ -647,7 +877,7 @@ pub(crate) fn create_gtk_menu_item(
             gtk_menu_item
         }
         MenuItemType::Radio => {
-            let radio_menu_item = gtk::RadioMenuItem::builder().draw_as_radio(false).sensitive(!item.disabled).active(item.checked).build();
+            let radio_menu_item = gtk::RadioMenuItem::builder().draw_as_radio(false).sensitive(!item.disabled).active(item.check_state.is_checked()).build();
             if let Some(radio_groups) = radio_groups {
                 if let Some(radio_group) = radio_groups.get(&item.name) {
                     radio_menu_item.join_group(Some(radio_group));
@@ -667,6 +897,17 @@ pub(crate) fn create_gtk_menu_item(
             item.gtk_menu_item_handle = from_gtk_menu_item(&gtk_menu_item);
             gtk_menu_item
         }
+        MenuItemType::Header => {
+            /* Separator items are never selected, so the header is skipped by keyboard navigation */
+            let separator_menu_item = gtk::SeparatorMenuItem::new();
+            separator_menu_item.style_context().add_class(HEADER_CLASS);
+            let label = Label::builder().label(parse_mnemonic(&item.label, config.mnemonic).text).xalign(0.0).build();
+            label.show();
+            separator_menu_item.add(&label);
+            let gtk_menu_item = separator_menu_item.upcast::<gtk::MenuItem>();
+            item.gtk_menu_item_handle = from_gtk_menu_item(&gtk_menu_item);
+            gtk_menu_item
+        }
         MenuItemType::Submenu => {
             let data = submenu_data.unwrap();
             let gtk_submenu = to_gtk_menu(data.gtk_submenu);
@@ -684,25 +925,57 @@ pub(crate) fn create_gtk_menu_item(
 
     gtk_menu_item.connect_activate(move |selected_gtk_menu_item| {
         let menu_item = get_menu_item_data_mut(selected_gtk_menu_item);
-        let current_menu_data = get_menu_data(menu_item.gtk_menu_handle);
         /* Get menu data, not submenu data */
-        let menu_data = if current_menu_data.parent_gtk_menu_handle > 0 {
-            get_menu_data(current_menu_data.parent_gtk_menu_handle)
-        } else {
-            current_menu_data
-        };
+        let main_gtk_menu_handle = get_main_menu_handle(menu_item.gtk_menu_handle);
+        let menu_data = get_menu_data_mut(main_gtk_menu_handle);
 
         /*
             Activate is triggered even when menu is hidden and the receiver receives the event as soon as it is shown.
-            So check its visibility from data, not from gtk::Menu.is_visible which returns always false at this time
+            So check its visibility from data, not from gtk::Menu.is_visible which returns always false at this time.
+            Only window shortcuts are handled while hidden.
         */
-        if menu_data.visible && selected_gtk_menu_item.get_sensitive() && should_send(selected_gtk_menu_item, menu_item) {
-            MenuEvent::send(MenuEvent {
-                item: menu_item.clone(),
-            });
-            MenuEvent::send_inner(InnerMenuEvent {
-                item: Some(menu_item.clone()),
-            });
+        let source = match (menu_data.visible, menu_data.activation_source) {
+            (true, source) => Some(source.unwrap_or(ActivationSource::Menu)),
+            (false, Some(ActivationSource::Shortcut)) => Some(ActivationSource::Shortcut),
+            (false, _) => None,
+        };
+
+        if let (Some(source), true) = (source, selected_gtk_menu_item.get_sensitive()) {
+            let toggled = is_check_menu_item(menu_item.menu_item_type) && !menu_item.suppress_event;
+
+            let should_send = should_send(selected_gtk_menu_item, menu_item);
+
+            if toggled {
+                MenuEvent::send(
+                    main_gtk_menu_handle,
+                    MenuEvent::Toggled {
+                        item: menu_item.clone(),
+                        check_state: menu_item.check_state,
+                    },
+                );
+            }
+
+            if should_send {
+                if menu_data.visible {
+                    menu_data.result = Some(PopupResult::Selected(menu_item.clone()));
+                }
+                MenuEvent::send(
+                    main_gtk_menu_handle,
+                    MenuEvent::Selected {
+                        item: menu_item.clone(),
+                        source,
+                    },
+                );
+            }
+        }
+    });
+
+    gtk_menu_item.connect_select(move |selected_gtk_menu_item| {
+        let menu_item = get_menu_item_data(selected_gtk_menu_item);
+        let main_gtk_menu_handle = get_main_menu_handle(menu_item.gtk_menu_handle);
+
+        if get_menu_data(main_gtk_menu_handle).visible {
+            MenuEvent::send(main_gtk_menu_handle, MenuEvent::Highlighted(menu_item.clone()));
         }
     });
 
@@ -711,7 +984,8 @@ pub(crate) fn create_gtk_menu_item(
 
     let css = get_menu_item_css(config);
     let css_provider = CssProvider::new();
-    css_provider.load_from_data(css.as_bytes()).unwrap();
+    /* Invalid Config is reported by MenuBuilder::build */
+    let _ = css_provider.load_from_data(css.as_bytes());
     let provider = css_provider.dynamic_cast::<StyleProvider>().unwrap();
     let provider_ptr: *mut GtkStyleProvider = provider.to_glib_none().0;
     unsafe { gtk_style_context_add_provider_for_screen(gdk_screen_get_default(), provider_ptr, STYLE_PROVIDER_PRIORITY_APPLICATION) };
@@ -720,7 +994,9 @@ pub(crate) fn create_gtk_menu_item(
 
     unsafe { gtk_menu_item.set_data("data", item.clone()) };
 
-    gtk_menu_item.show();
+    /* Gtk shows tooltips of insensitive widgets as well */
+    gtk_menu_item.set_tooltip_text(item.tooltip.as_deref());
+    gtk_menu_item.set_visible(item.visible);
 
     gtk_menu_item
 }
@@ -728,21 +1004,29 @@ pub(crate) fn create_gtk_menu_item(
 fn should_send(gtk_menu_item: &gtk::MenuItem, item: &mut MenuItem) -> bool {
     match item.menu_item_type {
         MenuItemType::Checkbox => {
-            item.checked = gtk_menu_item.downcast_ref::<gtk::CheckMenuItem>().unwrap().is_active();
+            let check_menu_item = gtk_menu_item.downcast_ref::<gtk::CheckMenuItem>().unwrap();
             if item.suppress_event {
+                /* Check state is already stored by MenuItem.set_check_state() */
                 item.suppress_event = false;
                 false
             } else {
+                /* Clicking a mixed MenuItem checks it */
+                let was_mixed = item.check_state == CheckState::Mixed;
+                item.check_state = check_menu_item.is_active().into();
+                if was_mixed {
+                    check_menu_item.set_inconsistent(false);
+                    update_custom_check_image(gtk_menu_item, item);
+                }
                 true
             }
         }
         MenuItemType::Radio => {
-            item.checked = gtk_menu_item.downcast_ref::<gtk::RadioMenuItem>().unwrap().is_active();
+            item.check_state = gtk_menu_item.downcast_ref::<gtk::RadioMenuItem>().unwrap().is_active().into();
             if item.suppress_event {
                 item.suppress_event = false;
                 false
             } else {
-                item.checked
+                item.check_state.is_checked()
             }
         }
         MenuItemType::Submenu => false,
@@ -3,25 +3,43 @@ use gtk::{
     glib::{
         monotonic_time,
         translate::{FromGlib, ToGlibPtr},
-        Cast, ObjectExt, SignalHandlerId,
+        Cast, ObjectExt, Propagation, SignalHandlerId,
     },
     prelude::{ContainerExt, CssProviderExt, GtkMenuExt, GtkMenuItemExt, GtkSettingsExt, MenuShellExt, SeatExt, StyleContextExt, WidgetExt},
     CssProvider, Widget, STYLE_PROVIDER_PRIORITY_APPLICATION,
 };
 use serde::{Deserialize, Serialize};
-use std::time::Duration;
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicU32, Ordering},
+};
 mod accelerator;
 mod builder;
 mod menu_item;
+mod search;
 mod style;
+#[cfg(test)]
+mod tests;
+mod theme;
+mod type_ahead;
 mod util;
-use crate::{config::*, InnerMenuEvent, MenuEvent, MenuItemType, MenuType, ThemeChangeFactor};
+use crate::{
+    config::*,
+    popup_channel,
+    validation::{collect_ids, validate_items, validate_new_accelerators},
+    AcceleratorConflict, ActivationSource, CloseReason, DismissReason, Error, MenuEvent, MenuItemType, MenuType, PopupResult, PopupSender, ThemeChangeFactor,
+};
 use accelerator::*;
 pub use builder::*;
 pub use menu_item::*;
+use search::reset_search;
 use style::*;
+pub use theme::{detect_system_color_scheme, detect_system_color_scheme_on, ColorSchemeSource, SystemColorScheme};
+use type_ahead::connect_type_ahead;
 use util::*;
 
+static POPUP_ID: AtomicU32 = AtomicU32::new(0);
+
 pub(crate) enum Container<'a> {
     Window(&'a gtk::Window),
     Menu(&'a Menu),
@@ -35,6 +53,12 @@ pub struct Menu {
     gtk_window_handle: isize,
 }
 
+impl From<gtk::glib::Error> for Error {
+    fn from(e: gtk::glib::Error) -> Self {
+        Error::Platform(Box::new(e))
+    }
+}
+
 impl Default for Menu {
     fn default() -> Self {
         Self {
@@ -57,7 +81,8 @@ impl Menu {
                 gtk_window.set_widget_name(widget_name);
                 let provider = CssProvider::new();
                 let css = get_window_css(config);
-                provider.load_from_data(css.as_bytes()).unwrap();
+                /* Invalid Config is reported by MenuBuilder::build */
+                let _ = provider.load_from_data(css.as_bytes());
                 gtk_window.style_context().add_provider(&provider, STYLE_PROVIDER_PRIORITY_APPLICATION);
             }
         }
@@ -104,7 +129,8 @@ impl Menu {
 
         let css = get_menu_css(config);
         let provider = CssProvider::new();
-        provider.load_from_data(css.as_bytes()).unwrap();
+        /* Invalid Config is reported by MenuBuilder::build */
+        let _ = provider.load_from_data(css.as_bytes());
         gtk_menu.style_context().add_provider(&provider, STYLE_PROVIDER_PRIORITY_APPLICATION);
 
         gtk_menu.show();
@@ -119,6 +145,10 @@ impl Menu {
         (menu, gtk_menu)
     }
 
+    pub(crate) fn main_menu_handle(&self) -> isize {
+        get_main_menu_handle(self.gtk_menu_handle)
+    }
+
     pub fn config(&self) -> Config {
         get_menu_data(self.gtk_menu_handle).config.clone()
     }
@@ -141,38 +171,54 @@ impl Menu {
     /// Gets the MenuItem with the specified id.
     pub fn get_menu_item_by_id(&self, id: &str) -> Option<MenuItem> {
         let gtk_menu = to_gtk_menu(self.gtk_menu_handle);
-        find_by_id(&gtk_menu.children(), id)
+        find_by_id(&menu_item_children(&gtk_menu), id).map(|gtk_menu_item| get_menu_item_data(&gtk_menu_item).clone())
     }
 
     /// Adds a MenuItem to the end of MenuItems.
-    pub fn append(&mut self, item: MenuItem) {
-        self.add_item(item, None);
+    pub fn append(&mut self, item: MenuItem) -> Result<(), Error> {
+        self.add_item(item, None)
     }
 
     /// Adds a MenuItem at the specified index.
-    pub fn insert(&mut self, item: MenuItem, index: u32) {
-        self.add_item(item, Some(index as i32));
+    pub fn insert(&mut self, item: MenuItem, index: u32) -> Result<(), Error> {
+        self.add_item(item, Some(index as i32))
     }
 
-    fn add_item(&mut self, mut item: MenuItem, index: Option<i32>) {
-        let gtk_menu = to_gtk_menu(self.gtk_menu_handle);
+    fn add_item(&mut self, mut item: MenuItem, index: Option<i32>) -> Result<(), Error> {
         let data = get_menu_data(self.gtk_menu_handle);
+        let main_menu_items = collect_menu_items(self.main_menu_handle());
+
+        let mut ids = HashSet::new();
+        collect_ids(&main_menu_items, &mut ids);
+        validate_items(std::slice::from_ref(&item), &mut ids)?;
+
+        let policy = data.config.accelerator_conflict;
+        let mut warnings = Vec::new();
+        let new_accelerators = validate_new_accelerators(&main_menu_items, &item, policy, &mut warnings)?;
+        validate_window_accelerators(self.gtk_window_handle, Some(get_accel_group(self.main_menu_handle())), &new_accelerators, policy, &mut warnings)?;
+        get_menu_data_mut(self.main_menu_handle()).accelerator_conflicts.extend(warnings);
+
+        let gtk_menu = to_gtk_menu(self.gtk_menu_handle);
 
         let gtk_menu_item = self.new_gtk_menu_item(&mut item, &data.config);
 
         if let Some(index) = index {
-            gtk_menu.insert(&gtk_menu_item, index);
+            /* The search field and its matches are at the top of the main menu */
+            let offset = gtk_menu.children().len() - menu_item_children(&gtk_menu).len();
+            gtk_menu.insert(&gtk_menu_item, index + offset as i32);
         } else {
             gtk_menu.append(&gtk_menu_item);
         }
 
         self.reset_haccel(&item);
 
-        if !gtk_menu.children().is_empty() {
+        if !menu_item_children(&gtk_menu).is_empty() {
             gtk_menu.set_sensitive(true);
         }
 
         self.after_change_items();
+
+        Ok(())
     }
 
     fn new_gtk_menu_item(&mut self, item: &mut MenuItem, config: &Config) -> gtk::MenuItem {
@@ -193,10 +239,10 @@ impl Menu {
     /// Removes the MenuItem at the specified index.
     pub fn remove_at(&mut self, index: u32) {
         let gtk_menu = to_gtk_menu(self.gtk_menu_handle);
-        if let Some(remove_gtk_menu_item) = gtk_menu.children().get(index as usize) {
+        if let Some(remove_gtk_menu_item) = menu_item_children(&gtk_menu).get(index as usize) {
             gtk_menu.remove(remove_gtk_menu_item);
 
-            if gtk_menu.children().is_empty() {
+            if menu_item_children(&gtk_menu).is_empty() {
                 gtk_menu.set_sensitive(false);
             }
 
@@ -207,7 +253,7 @@ impl Menu {
     /// Removes the MenuItem.
     pub fn remove(&mut self, item: &MenuItem) {
         let gtk_menu = to_gtk_menu(self.gtk_menu_handle);
-        let maybe_index = index_of_item(&gtk_menu.children(), item.uuid);
+        let maybe_index = index_of_item(&menu_item_children(&gtk_menu), item.uuid);
 
         if let Some(index) = maybe_index {
             self.remove_at(index as u32);
@@ -228,124 +274,313 @@ impl Menu {
         add_accelerators_from_menu_item(gtk_menu_handle, item);
     }
 
+    /// Returns the accelerator conflicts found under AcceleratorConflictPolicy::Warn and clears them.
+    pub fn take_accelerator_conflicts(&self) -> Vec<AcceleratorConflict> {
+        std::mem::take(&mut get_menu_data_mut(self.main_menu_handle()).accelerator_conflicts)
+    }
+
     /// Shows Menu at the specified point.
     pub fn popup_at(&self, x: i32, y: i32) {
-        let gtk_window = to_gtk_window(self.gtk_window_handle);
-        let gtk_menu = to_gtk_menu(self.gtk_menu_handle);
+        show_popup(self.gtk_window_handle, self.gtk_menu_handle, x, y, None);
+    }
+
+    /// Shows Menu asynchronously at the specified point and returns how the popup ended.
+    pub async fn popup_at_async(&self, x: i32, y: i32) -> PopupResult {
+        let (tx, rx) = popup_channel();
+        let (gtk_window_handle, gtk_menu_handle) = (self.gtk_window_handle, self.gtk_menu_handle);
+
+        gtk::glib::MainContext::default().invoke(move || {
+            show_popup(gtk_window_handle, gtk_menu_handle, x, y, Some(tx));
+        });
 
-        let mut event = gdk::Event::new(gdk::EventType::ButtonPress);
-        event.set_device(gtk_window.display().default_seat().and_then(|d| d.pointer()).as_ref());
+        /* The sender is dropped without result only when Menu is gone */
+        rx.recv().await.unwrap_or(PopupResult::Dismissed(DismissReason::Destroyed))
+    }
+}
 
-        let window = gtk_window.window().unwrap();
+impl Menu {
+    /// Returns true while the popup is shown.
+    pub fn is_open(&self) -> bool {
+        get_menu_data(self.main_menu_handle()).visible
+    }
 
-        let event_ffi: *mut GdkEvent = event.to_glib_none().0;
-        if !event_ffi.is_null() {
-            let time = monotonic_time() / 1000;
-            unsafe {
-                (*event_ffi).button.time = time as u32;
-            }
+    /// Activates the MenuItem with the specified id in the same way as a click.
+    /// Returns false if the popup is not shown or the MenuItem cannot be selected.
+    pub fn activate_by_id(&self, id: &str) -> bool {
+        let Some(gtk_menu_item) = self.find_operable_item(id) else {
+            return false;
+        };
+
+        let menu_item = get_menu_item_data(&gtk_menu_item);
+        if !menu_item.menu_item_type.is_selectable() || menu_item.menu_item_type == MenuItemType::Submenu {
+            return false;
         }
 
-        #[cfg(feature = "accelerator")]
-        connect_accelerator(&gtk_menu, self.gtk_menu_handle, self.gtk_window_handle);
-        toggle_visible(&gtk_window, self.gtk_menu_handle);
-        gtk_menu.popup_at_rect(&window, &Rectangle::new(x, y, 0, 0), Gravity::NorthWest, Gravity::NorthWest, Some(&event));
-        toggle_visible(&gtk_window, self.gtk_menu_handle);
+        /* Same as a click, the menu shell is deactivated first and the item is activated next */
+        let Some(gtk_menu_shell) = gtk_menu_item.parent().and_then(|parent| parent.downcast::<gtk::MenuShell>().ok()) else {
+            return false;
+        };
+        get_menu_data_mut(self.main_menu_handle()).activation_source = Some(ActivationSource::Program);
+        gtk_menu_shell.activate_item(&gtk_menu_item, true);
+        get_menu_data_mut(self.main_menu_handle()).activation_source = None;
+
+        /* Deactivating a submenu which is not shown does not close the main menu */
+        let gtk_menu = to_gtk_menu(self.main_menu_handle());
+        if gtk_menu.is_visible() {
+            gtk_menu.deactivate();
+        }
+
+        true
     }
 
-    /// Shows Menu asynchronously at the specified point and returns the selected MenuItem if any.
-    pub async fn popup_at_async(&self, x: i32, y: i32) -> Option<MenuItem> {
-        let (tx, rx) = smol::channel::bounded(1);
-        let (gtk_window_handle, gtk_menu_handle) = (self.gtk_window_handle, self.gtk_menu_handle);
+    /// Selects the MenuItem with the specified id in the same way as a pointer hover.
+    /// Returns false if the popup is not shown or the MenuItem cannot be highlighted.
+    pub fn highlight(&self, id: &str) -> bool {
+        let Some(gtk_menu_item) = self.find_operable_item(id) else {
+            return false;
+        };
 
-        gtk::glib::MainContext::default().invoke(move || {
-            gtk::glib::spawn_future_local(async move {
-                let gtk_window = to_gtk_window(gtk_window_handle);
-                let gtk_menu = to_gtk_menu(gtk_menu_handle);
+        if !get_menu_item_data(&gtk_menu_item).menu_item_type.is_selectable() {
+            return false;
+        }
 
-                let mut event = gdk::Event::new(gdk::EventType::ButtonPress);
-                event.set_device(gtk_window.display().default_seat().and_then(|d| d.pointer()).as_ref());
+        match gtk_menu_item.parent().and_then(|parent| parent.downcast::<gtk::MenuShell>().ok()) {
+            Some(gtk_menu_shell) => {
+                gtk_menu_shell.select_item(&gtk_menu_item);
+                true
+            }
+            None => false,
+        }
+    }
 
-                let window = gtk_window.window().unwrap();
+    /// Closes the popup in the same way as the Escape key.
+    pub fn dismiss(&self) {
+        if self.is_open() {
+            to_gtk_menu(self.main_menu_handle()).cancel();
+        }
+    }
 
-                let event_ffi: *mut GdkEvent = event.to_glib_none().0;
-                if !event_ffi.is_null() {
-                    let time = monotonic_time() / 1000;
-                    unsafe {
-                        (*event_ffi).button.time = time as u32;
-                    }
-                }
+    /// Closes the popup and its submenus. A pending popup is resolved as [`DismissReason::Closed`].
+    pub fn close(&self) {
+        let gtk_menu_handle = self.main_menu_handle();
+        if !get_menu_data(gtk_menu_handle).visible {
+            return;
+        }
 
-                #[cfg(feature = "accelerator")]
-                connect_accelerator(&gtk_menu, gtk_menu_handle, gtk_window_handle);
+        /* Deactivating the menu shell hides open submenus too */
+        to_gtk_menu(gtk_menu_handle).deactivate();
 
-                toggle_visible(&gtk_window, gtk_menu_handle);
+        /* Finish here instead of waiting for "hide" so that the popup is resolved when this returns */
+        set_visible(&to_gtk_window(self.gtk_window_handle), gtk_menu_handle, false);
+        finish_popup(gtk_menu_handle, PopupResult::Dismissed(DismissReason::Closed));
+    }
 
-                gtk_menu.popup_at_rect(&window, &Rectangle::new(x, y, 0, 0), Gravity::NorthWest, Gravity::NorthWest, Some(&event));
+    /// Binds the accelerators of MenuItems as window shortcuts which work while Menu is closed and the window has focus.
+    /// Activations are notified as MenuEvent::Selected with ActivationSource::Shortcut. Hidden or disabled MenuItems are ignored.
+    #[cfg(feature = "accelerator")]
+    pub fn set_window_shortcuts(&self, enabled: bool) {
+        get_menu_data_mut(self.main_menu_handle()).window_shortcuts = enabled;
+    }
 
-                let mut item = None;
+    fn find_operable_item(&self, id: &str) -> Option<gtk::MenuItem> {
+        if !self.is_open() {
+            return None;
+        }
 
-                let signal = gtk_menu.connect_hide(move |_| {
-                    MenuEvent::send_inner(InnerMenuEvent {
-                        item: None,
-                    });
-                });
+        let gtk_menu = to_gtk_menu(self.main_menu_handle());
+        let gtk_menu_item = find_by_id(&menu_item_children(&gtk_menu), id)?.downcast::<gtk::MenuItem>().ok()?;
 
-                if let Ok(event) = MenuEvent::innner_receiver().recv().await {
-                    item = event.item;
-                }
+        /* Walk up to the main menu since the MenuItem cannot be reached through a disabled or hidden submenu */
+        let mut widget: Widget = gtk_menu_item.clone().upcast();
+        loop {
+            let menu_item = get_menu_item_data(&widget);
+            if menu_item.disabled || !menu_item.visible {
+                return None;
+            }
 
-                gtk_menu.disconnect(signal);
+            /* The attach widget of the main menu is the window, which has no MenuItem */
+            match widget.parent().and_then(|parent| parent.downcast::<gtk::Menu>().ok()).and_then(|gtk_menu| gtk_menu.attach_widget()) {
+                Some(parent) if unsafe { parent.data::<MenuItem>("data").is_some() } => widget = parent,
+                _ => break,
+            }
+        }
 
-                toggle_visible(&gtk_window, gtk_menu_handle);
+        Some(gtk_menu_item)
+    }
+}
 
-                /*
-                    Wait 50 ms for "activate" event.
-                    "activate" by click occurs after automatic menu "hide", so event can have menu item.
-                    "activate" by keypress occurs before menu "hide", so event is none that should be dismissed.
-                */
-                smol::Timer::after(Duration::from_millis(50)).await;
-                if let Ok(event) = MenuEvent::innner_receiver().try_recv() {
-                    if event.item.is_some() {
-                        item = event.item;
-                    }
-                }
+fn show_popup(gtk_window_handle: isize, gtk_menu_handle: isize, x: i32, y: i32, sender: Option<PopupSender>) {
+    let gtk_window = to_gtk_window(gtk_window_handle);
+    let gtk_menu = to_gtk_menu(gtk_menu_handle);
 
-                let _ = tx.try_send(item);
-            });
-        });
+    let mut event = gdk::Event::new(gdk::EventType::ButtonPress);
+    event.set_device(gtk_window.display().default_seat().and_then(|d| d.pointer()).as_ref());
 
-        rx.recv().await.unwrap_or_default()
+    let window = gtk_window.window().unwrap();
+
+    let event_ffi: *mut GdkEvent = event.to_glib_none().0;
+    if !event_ffi.is_null() {
+        let time = monotonic_time() / 1000;
+        unsafe {
+            (*event_ffi).button.time = time as u32;
+        }
     }
+
+    /* Finish the current popup so that its result never leaks into the new one */
+    if get_menu_data(gtk_menu_handle).visible {
+        set_visible(&gtk_window, gtk_menu_handle, false);
+        finish_popup(gtk_menu_handle, PopupResult::Dismissed(DismissReason::Replaced));
+    }
+
+    let menu_data = get_menu_data_mut(gtk_menu_handle);
+    menu_data.popup_id = POPUP_ID.fetch_add(1, Ordering::Relaxed);
+    menu_data.popup_sender = sender;
+
+    set_visible(&gtk_window, gtk_menu_handle, true);
+    gtk_menu.popup_at_rect(&window, &Rectangle::new(x, y, 0, 0), Gravity::NorthWest, Gravity::NorthWest, Some(&event));
 }
 
-fn toggle_visible(gtk_window: &gtk::Window, gtk_menu_handle: isize) {
+fn set_visible(gtk_window: &gtk::Window, gtk_menu_handle: isize, visible: bool) {
     let menu_data = get_menu_data_mut(gtk_menu_handle);
-    menu_data.visible = !menu_data.visible;
+    if menu_data.visible == visible {
+        return;
+    }
+
+    menu_data.visible = visible;
+    #[cfg(feature = "accelerator")]
+    menu_data.chord_state.reset();
+    menu_data.type_ahead.reset();
     if menu_data.visible {
+        menu_data.result = None;
+        reset_search(gtk_menu_handle);
         add_accel_group(gtk_window, gtk_menu_handle);
     } else {
         remove_accel_group(gtk_window, gtk_menu_handle);
     }
 }
 
+pub(crate) fn connect_menu_events(gtk_menu: &gtk::Menu, menu: &Menu) {
+    let (gtk_menu_handle, gtk_window_handle) = (menu.gtk_menu_handle, menu.gtk_window_handle);
+
+    connect_type_ahead(gtk_menu, gtk_menu_handle);
+
+    /* Escape is bound to "cancel" of the menu shell including submenus */
+    gtk_menu.connect_cancel(move |_| {
+        set_dismiss_reason(get_main_menu_handle(gtk_menu_handle), DismissReason::Escape);
+    });
+
+    /* "show" is not emitted on the first popup because gtk::Menu is already shown when created, so use "map" instead */
+    if menu.menu_type == MenuType::Main {
+        gtk_menu.connect_map(move |_| {
+            if get_menu_data(gtk_menu_handle).visible {
+                MenuEvent::send(gtk_menu_handle, MenuEvent::Opened);
+            }
+        });
+
+        gtk_menu.connect_grab_broken_event(move |_, _| {
+            set_dismiss_reason(gtk_menu_handle, DismissReason::FocusLost);
+            Propagation::Proceed
+        });
+
+        gtk_menu.connect_hide(move |_| {
+            let menu_data = get_menu_data(gtk_menu_handle);
+            if !menu_data.visible {
+                return;
+            }
+
+            /*
+                "activate" by click occurs after "hide", so finish the popup after pending events are processed.
+                The idle callback finishes only the popup that was hidden, not one shown after it.
+            */
+            let popup_id = menu_data.popup_id;
+            let weak_gtk_menu = to_gtk_menu(gtk_menu_handle).downgrade();
+            gtk::glib::idle_add_local_once(move || {
+                if weak_gtk_menu.upgrade().is_none() {
+                    return;
+                }
+
+                let menu_data = get_menu_data_mut(gtk_menu_handle);
+                if !menu_data.visible || menu_data.popup_id != popup_id {
+                    return;
+                }
+
+                /* Menu shell is deactivated by a click outside when no other reason is recorded */
+                let result = menu_data.result.take().unwrap_or(PopupResult::Dismissed(DismissReason::ClickOutside));
+                set_visible(&to_gtk_window(gtk_window_handle), gtk_menu_handle, false);
+                finish_popup(gtk_menu_handle, result);
+            });
+        });
+
+        gtk_menu.connect_destroy(move |_| {
+            let menu_data = get_menu_data_mut(gtk_menu_handle);
+            if menu_data.visible {
+                menu_data.visible = false;
+                finish_popup(gtk_menu_handle, PopupResult::Dismissed(DismissReason::Destroyed));
+            }
+        });
+    } else {
+        gtk_menu.connect_map(move |gtk_submenu| {
+            send_submenu_event(gtk_submenu, gtk_menu_handle, MenuEvent::SubmenuOpened);
+        });
+
+        gtk_menu.connect_hide(move |gtk_submenu| {
+            send_submenu_event(gtk_submenu, gtk_menu_handle, MenuEvent::SubmenuClosed);
+        });
+    }
+}
+
+fn set_dismiss_reason(gtk_menu_handle: isize, reason: DismissReason) {
+    let menu_data = get_menu_data_mut(gtk_menu_handle);
+    if menu_data.visible && menu_data.result.is_none() {
+        menu_data.result = Some(PopupResult::Dismissed(reason));
+    }
+}
+
+/// Resolves the current popup. Callers make sure it is called once per popup by checking visible.
+fn finish_popup(gtk_menu_handle: isize, result: PopupResult) {
+    let sender = get_menu_data_mut(gtk_menu_handle).popup_sender.take();
+
+    MenuEvent::send(
+        gtk_menu_handle,
+        MenuEvent::Closed {
+            reason: CloseReason::from(&result),
+        },
+    );
+
+    if let Some(sender) = sender {
+        let _ = sender.try_send(result);
+    }
+}
+
+fn send_submenu_event(gtk_submenu: &gtk::Menu, gtk_submenu_handle: isize, to_event: fn(MenuItem) -> MenuEvent) {
+    let main_gtk_menu_handle = get_main_menu_handle(gtk_submenu_handle);
+    if !get_menu_data(main_gtk_menu_handle).visible {
+        return;
+    }
+
+    /* Submenu is attached to its parent gtk::MenuItem */
+    if let Some(gtk_menu_item) = gtk_submenu.attach_widget() {
+        MenuEvent::send(main_gtk_menu_handle, to_event(get_menu_item_data(&gtk_menu_item).clone()));
+    }
+}
+
 pub(crate) fn collect_menu_items(gtk_menu_handle: isize) -> Vec<MenuItem> {
     let gtk_menu = to_gtk_menu(gtk_menu_handle);
-    gtk_menu.children().iter().map(|item| get_menu_item_data(item).clone()).collect()
+    menu_item_children(&gtk_menu).iter().map(|item| get_menu_item_data(item).clone()).collect()
 }
 
-fn find_by_id(gtk_menu_items: &Vec<Widget>, id: &str) -> Option<MenuItem> {
+fn find_by_id(gtk_menu_items: &Vec<Widget>, id: &str) -> Option<Widget> {
     let item_id = id.to_string();
     for gtk_menu_item in gtk_menu_items {
         let menu_item = get_menu_item_data(gtk_menu_item);
         if menu_item.id == item_id {
-            return Some(menu_item.clone());
+            return Some(gtk_menu_item.clone());
         }
 
         if menu_item.menu_item_type == MenuItemType::Submenu {
             let gtk_submenu = to_gtk_menu(menu_item.submenu.as_ref().unwrap().gtk_menu_handle);
-            if let Some(menu_item) = find_by_id(&gtk_submenu.children(), id) {
-                return Some(menu_item);
+            if let Some(gtk_menu_item) = find_by_id(&menu_item_children(&gtk_submenu), id) {
+                return Some(gtk_menu_item);
             }
         }
     }
@@ -361,7 +596,7 @@ fn index_of_item(gtk_menu_items: &[Widget], uuid: u16) -> Option<usize> {
 
         if menu_item.menu_item_type == MenuItemType::Submenu {
             let gtk_submenu = to_gtk_menu(menu_item.submenu.as_ref().unwrap().gtk_menu_handle);
-            if let Some(index) = index_of_item(&gtk_submenu.children(), uuid) {
+            if let Some(index) = index_of_item(&menu_item_children(&gtk_submenu), uuid) {
                 return Some(index);
             }
         }
@@ -383,6 +618,11 @@ fn on_theme_change(menu_type: MenuType, gtk_menu_handle: isize, maybe_preferred_
         return;
     }
 
+    /* System theme follows the desktop setting only, which is handled by the system theme watcher */
+    if current_them == Theme::System && factor == ThemeChangeFactor::App {
+        return;
+    }
+
     let should_be_dark = match factor {
         ThemeChangeFactor::User => {
             let preferred_theme = maybe_preferred_theme.unwrap();
@@ -399,7 +639,7 @@ fn on_theme_change(menu_type: MenuType, gtk_menu_handle: isize, maybe_preferred_
                 is_sys_dark()
             }
         }
-        ThemeChangeFactor::SystemSetting => false,
+        ThemeChangeFactor::SystemSetting => is_sys_dark(),
     };
 
     let new_theme = match maybe_preferred_theme {
@@ -427,7 +667,7 @@ fn on_theme_change(menu_type: MenuType, gtk_menu_handle: isize, maybe_preferred_
     }
     gtk_menu.set_widget_name(widget_name);
 
-    change_style(&gtk_menu.children(), new_theme, widget_name, data);
+    change_style(&menu_item_children(&gtk_menu), new_theme, widget_name, data);
 }
 
 fn change_style(gtk_menu_items: &Vec<Widget>, new_theme: Theme, widget_name: &str, data: &MenuData) {
@@ -446,7 +686,7 @@ fn change_style(gtk_menu_items: &Vec<Widget>, new_theme: Theme, widget_name: &st
                 }
             }
             gtk_submenu.set_widget_name(widget_name);
-            change_style(&gtk_submenu.children(), new_theme, widget_name, data);
+            change_style(&menu_item_children(&gtk_submenu), new_theme, widget_name, data);
         }
     }
 }
@@ -0,0 +1,182 @@
+use super::{
+    collect_menu_items,
+    style::{get_search_css, get_widget_name, SEARCH_FIELD_NAME, SEARCH_PATH_CLASS},
+    util::{get_menu_data, get_menu_data_mut, get_menu_item_data, menu_item_children, to_gtk_menu, to_gtk_menu_item},
+};
+use crate::{mnemonic::parse_mnemonic, search::search_items, MenuEvent};
+use gtk::{
+    ffi::{gtk_style_context_add_provider_for_screen, GtkStyleProvider},
+    gdk::{ffi::gdk_screen_get_default, keys::constants as keys, ModifierType},
+    glib::{translate::ToGlibPtr, Cast, Propagation},
+    prelude::{BoxExt, ContainerExt, CssProviderExt, EntryExt, GtkMenuItemExt, LabelExt, MenuShellExt, StyleContextExt, WidgetExt},
+    CssProvider, Label, Orientation, StyleProvider, STYLE_PROVIDER_PRIORITY_APPLICATION,
+};
+
+/// Search field of the main Menu and the matches listed under it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SearchState {
+    query: String,
+    gtk_entry: Option<gtk::Entry>,
+    gtk_results: Vec<gtk::MenuItem>,
+}
+
+impl SearchState {
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.gtk_entry.is_some()
+    }
+}
+
+/// Adds the search field at the top of the main Menu.
+pub(crate) fn add_search_field(gtk_menu: &gtk::Menu, gtk_menu_handle: isize) {
+    let config = &get_menu_data(gtk_menu_handle).config;
+    let Some(settings) = &config.search else {
+        return;
+    };
+
+    /* gtk::Menu keeps the keyboard grab, so the entry only displays the query typed in the menu */
+    let gtk_entry = gtk::Entry::new();
+    gtk_entry.set_placeholder_text(Some(&settings.placeholder));
+    gtk_entry.set_primary_icon_name(Some("edit-find-symbolic"));
+    gtk_entry.set_can_focus(false);
+
+    /* Separator items are never selected, so the field does not take the highlight from MenuItems */
+    let gtk_field_item = gtk::SeparatorMenuItem::new();
+    gtk_field_item.set_widget_name(SEARCH_FIELD_NAME);
+    gtk_field_item.add(&gtk_entry);
+    gtk_field_item.show_all();
+    gtk_menu.prepend(&gtk_field_item);
+
+    let css = get_search_css(config);
+    let css_provider = CssProvider::new();
+    /* Invalid Config is reported by MenuBuilder::build */
+    let _ = css_provider.load_from_data(css.as_bytes());
+    let provider = css_provider.dynamic_cast::<StyleProvider>().unwrap();
+    let provider_ptr: *mut GtkStyleProvider = provider.to_glib_none().0;
+    unsafe { gtk_style_context_add_provider_for_screen(gdk_screen_get_default(), provider_ptr, STYLE_PROVIDER_PRIORITY_APPLICATION) };
+
+    get_menu_data_mut(gtk_menu_handle).search.gtk_entry = Some(gtk_entry);
+
+    gtk_menu.connect_key_press_event(move |gtk_menu, event| {
+        let menu_data = get_menu_data_mut(gtk_menu_handle);
+
+        /* Keys with Ctrl, Alt or Super are left to accelerators */
+        if !menu_data.visible || event.state().intersects(ModifierType::CONTROL_MASK | ModifierType::MOD1_MASK | ModifierType::SUPER_MASK) {
+            return Propagation::Proceed;
+        }
+
+        let mut query = menu_data.search.query.clone();
+        let keyval = event.keyval();
+
+        if keyval == keys::Return || keyval == keys::KP_Enter || keyval == keys::ISO_Enter {
+            /* Enter on a highlighted match is handled by gtk::Menu as usual */
+            let gtk_results = &menu_data.search.gtk_results;
+            let match_selected = gtk_menu.selected_item().is_some_and(|selected| gtk_results.iter().any(|gtk_result| gtk_result.upcast_ref::<gtk::Widget>() == &selected));
+            return match gtk_results.first().cloned() {
+                Some(gtk_result) if !match_selected => {
+                    gtk_menu.activate_item(&gtk_result, true);
+                    Propagation::Stop
+                }
+                _ => Propagation::Proceed,
+            };
+        }
+
+        if keyval == keys::BackSpace {
+            if query.pop().is_none() {
+                return Propagation::Proceed;
+            }
+        } else if keyval == keys::Escape {
+            /* Escape clears the query first and closes Menu next */
+            if query.is_empty() {
+                return Propagation::Proceed;
+            }
+            query.clear();
+        } else {
+            let Some(c) = keyval.to_unicode().filter(|c| !c.is_control()) else {
+                return Propagation::Proceed;
+            };
+
+            /* Space activates the highlighted MenuItem unless typing */
+            if c == ' ' && query.is_empty() {
+                return Propagation::Proceed;
+            }
+            query.push(c);
+        }
+
+        update_search(gtk_menu_handle, &query);
+
+        Propagation::Stop
+    });
+}
+
+/// Clears the query and shows the MenuItems again.
+pub(crate) fn reset_search(gtk_menu_handle: isize) {
+    if get_menu_data(gtk_menu_handle).search.is_enabled() {
+        update_search(gtk_menu_handle, "");
+    }
+}
+
+/// Lists the matches of the query in place of the MenuItems. An empty query restores the MenuItems.
+fn update_search(gtk_menu_handle: isize, query: &str) {
+    let gtk_menu = to_gtk_menu(gtk_menu_handle);
+    let menu_data = get_menu_data_mut(gtk_menu_handle);
+    let state = &mut menu_data.search;
+
+    state.query = query.to_string();
+    if let Some(gtk_entry) = &state.gtk_entry {
+        gtk_entry.set_text(query);
+    }
+
+    for gtk_result in state.gtk_results.drain(..) {
+        gtk_menu.remove(&gtk_result);
+    }
+
+    let searching = !query.is_empty();
+    for gtk_menu_item in menu_item_children(&gtk_menu) {
+        /* Keep the visibility of MenuItem itself so that it is restored after search */
+        gtk_menu_item.set_visible(!searching && get_menu_item_data(&gtk_menu_item).visible);
+    }
+
+    if !searching {
+        return;
+    }
+
+    let max_results = menu_data.config.search.as_ref().map_or(0, |settings| settings.max_results);
+    let widget_name = get_widget_name(menu_data.config.theme);
+    let matches = search_items(&collect_menu_items(gtk_menu_handle), query, menu_data.config.mnemonic, max_results);
+
+    for (index, search_match) in matches.iter().enumerate() {
+        let box_container = gtk::Box::new(Orientation::Horizontal, 0);
+        let label = Label::new(Some(&parse_mnemonic(&search_match.item.label, menu_data.config.mnemonic).text));
+        label.set_xalign(0.0);
+        box_container.pack_start(&label, true, true, 0);
+
+        let path = Label::new(Some(&search_match.path_text()));
+        path.style_context().add_class(SEARCH_PATH_CLASS);
+        box_container.pack_end(&path, false, false, 0);
+
+        let gtk_result = gtk::MenuItem::new();
+        gtk_result.set_widget_name(widget_name);
+        gtk_result.set_tooltip_text(search_match.item.tooltip.as_deref());
+        gtk_result.add(&box_container);
+        gtk_result.show_all();
+
+        /* Activate the MenuItem itself so that it is notified in the same way as a click */
+        let gtk_menu_item_handle = search_match.item.gtk_menu_item_handle;
+        gtk_result.connect_activate(move |_| {
+            to_gtk_menu_item(gtk_menu_item_handle).activate();
+        });
+
+        gtk_result.connect_select(move |_| {
+            let menu_item = get_menu_item_data(&to_gtk_menu_item(gtk_menu_item_handle)).clone();
+            MenuEvent::send(gtk_menu_handle, MenuEvent::Highlighted(menu_item));
+        });
+
+        /* The search field is the first child */
+        gtk_menu.insert(&gtk_result, index as i32 + 1);
+        menu_data.search.gtk_results.push(gtk_result);
+    }
+
+    if let Some(gtk_result) = menu_data.search.gtk_results.first() {
+        gtk_menu.select_item(gtk_result);
+    }
+}
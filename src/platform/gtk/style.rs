@@ -1,4 +1,5 @@
 use super::{
+    theme::{current_system_color_scheme, ColorSchemeSource},
     to_font_weight,
     util::{get_custom_check_width, is_svg},
 };
@@ -13,12 +14,26 @@ const WIDGET_NAME: &str = "wcpopup";
 const DARK_WIDGET_NAME: &str = "wcpopup-dark";
 const LIGHT_WIDGET_NAME: &str = "wcpopup-light";
 pub(crate) const CUSTOM_CHECKMARK_NAME: &str = "wcpopup-check";
+pub(crate) const SEARCH_FIELD_NAME: &str = "wcpopup-search";
+pub(crate) const SEARCH_PATH_CLASS: &str = "search-path";
+pub(crate) const DESCRIPTION_CLASS: &str = "description";
+pub(crate) const HEADER_CLASS: &str = "header";
 
 pub(crate) fn get_widget_name<'a>(theme: Theme) -> &'a str {
     match theme {
         Theme::Dark => DARK_WIDGET_NAME,
         Theme::Light => LIGHT_WIDGET_NAME,
-        Theme::System => WIDGET_NAME,
+        Theme::System => get_system_widget_name(),
+    }
+}
+
+/// The portal color-scheme can differ from the Gtk theme as on GNOME 42+, so its colors are applied.
+/// Otherwise the Gtk theme's own colors are used.
+fn get_system_widget_name<'a>() -> &'a str {
+    match current_system_color_scheme() {
+        scheme if scheme.source != ColorSchemeSource::Portal => WIDGET_NAME,
+        scheme if scheme.is_dark => DARK_WIDGET_NAME,
+        _ => LIGHT_WIDGET_NAME,
     }
 }
 
@@ -220,6 +235,36 @@ pub(crate) fn get_menu_item_css(config: &Config) -> String {
         String::new()
     };
 
+    let has_custom_check_image = config.icon.as_ref().unwrap().check.as_ref().is_some_and(|check| !matches!(check.icon, MenuIconKind::Path(_)));
+
+    /* Path icon for mixed state replaces Gtk's inconsistent look unless checkmark is shown by gtk::Image */
+    let mixed = match config.icon.as_ref().unwrap().mixed.as_ref().map(|mixed| &mixed.icon) {
+        Some(MenuIconKind::Path(icon)) if !has_custom_check_image => {
+            if is_svg(&icon.path) {
+                format!(
+                    r#"
+                        -gtk-icon-source: -gtk-recolor(url("{}"));
+                    "#,
+                    icon.path.display(),
+                )
+            } else {
+                format!(
+                    r#"
+                        -gtk-icon-source: url("{}");
+                    "#,
+                    icon.path.display(),
+                )
+            }
+        }
+        _ => String::new(),
+    };
+
+    /* Without Data or Svg icon for mixed state, custom checkmark is dimmed */
+    let mixed_image_opacity = match config.icon.as_ref().unwrap().mixed.as_ref().map(|mixed| &mixed.icon) {
+        Some(MenuIconKind::Data(_)) | Some(MenuIconKind::Svg(_)) => 1.0,
+        _ => 0.5,
+    };
+
     let arrow = if let Some(arrow) = &config.icon.as_ref().unwrap().arrow {
         match &arrow.icon {
             MenuIconKind::Path(icon) => {
@@ -300,6 +345,11 @@ pub(crate) fn get_menu_item_css(config: &Config) -> String {
             #{LIGHT_WIDGET_NAME} check:checked+box image#{CUSTOM_CHECKMARK_NAME}:first-child{{
                 opacity:1;
             }}
+            #{WIDGET_NAME} check:indeterminate+box image#{CUSTOM_CHECKMARK_NAME}:first-child,
+            #{DARK_WIDGET_NAME} check:indeterminate+box image#{CUSTOM_CHECKMARK_NAME}:first-child,
+            #{LIGHT_WIDGET_NAME} check:indeterminate+box image#{CUSTOM_CHECKMARK_NAME}:first-child{{
+                opacity:{};
+            }}
             #{WIDGET_NAME} check,
             #{DARK_WIDGET_NAME} check,
             #{LIGHT_WIDGET_NAME} check{{
@@ -310,6 +360,11 @@ pub(crate) fn get_menu_item_css(config: &Config) -> String {
             #{LIGHT_WIDGET_NAME} check:checked{{
                 {}
             }}
+            #{WIDGET_NAME} check:indeterminate,
+            #{DARK_WIDGET_NAME} check:indeterminate,
+            #{LIGHT_WIDGET_NAME} check:indeterminate{{
+                {}
+            }}
             #{DARK_WIDGET_NAME} check,
             #{DARK_WIDGET_NAME} check:checked {{
                 color: {};
@@ -396,6 +451,47 @@ pub(crate) fn get_menu_item_css(config: &Config) -> String {
             #{LIGHT_WIDGET_NAME} label{{
                 {}
             }}
+
+            #{WIDGET_NAME} label.{DESCRIPTION_CLASS} {{
+                font-size: {}px;
+            }}
+            #{DARK_WIDGET_NAME} label.{DESCRIPTION_CLASS} {{
+                font-size: {}px;
+                color: {};
+            }}
+            #{LIGHT_WIDGET_NAME} label.{DESCRIPTION_CLASS} {{
+                font-size: {}px;
+                color: {};
+            }}
+            menuitem#{DARK_WIDGET_NAME}:disabled label.{DESCRIPTION_CLASS} {{
+                color: {};
+            }}
+            menuitem#{LIGHT_WIDGET_NAME}:disabled label.{DESCRIPTION_CLASS} {{
+                color: {};
+            }}
+
+            separator#{WIDGET_NAME}.{HEADER_CLASS},
+            separator#{DARK_WIDGET_NAME}.{HEADER_CLASS},
+            separator#{LIGHT_WIDGET_NAME}.{HEADER_CLASS} {{
+                background: none;
+                min-height: 0px;
+                margin: 0px;
+                padding: {}px {}px;
+            }}
+            separator#{WIDGET_NAME}.{HEADER_CLASS} label {{
+                font-size: {}px;
+                font-weight: bold;
+            }}
+            separator#{DARK_WIDGET_NAME}.{HEADER_CLASS} label {{
+                font-size: {}px;
+                font-weight: bold;
+                color: {};
+            }}
+            separator#{LIGHT_WIDGET_NAME}.{HEADER_CLASS} label {{
+                font-size: {}px;
+                font-weight: bold;
+                color: {};
+            }}
         "#,
         /* accelerator */
         config.font.dark_font_size,
@@ -403,10 +499,14 @@ pub(crate) fn get_menu_item_css(config: &Config) -> String {
         weight,
         to_rgba_string(config.color.dark.accelerator),
         to_rgba_string(config.color.light.accelerator),
+        /* mixed custom checkmark */
+        mixed_image_opacity,
         /* check */
         check,
         /* checked */
         checked,
+        /* mixed */
+        mixed,
         /* check color */
         to_rgba_string(config.color.dark.color),
         to_rgba_string(config.color.dark.background_color),
@@ -444,7 +544,46 @@ pub(crate) fn get_menu_item_css(config: &Config) -> String {
         to_rgba_string(config.color.dark.separator),
         to_rgba_string(config.color.light.separator),
         /* padding for custom checkmark */
-        get_label_padding(config)
+        get_label_padding(config),
+        /* description */
+        config.font.dark_description_font_size,
+        config.font.dark_description_font_size,
+        to_rgba_string(config.color.dark.description),
+        config.font.light_description_font_size,
+        to_rgba_string(config.color.light.description),
+        to_rgba_string(config.color.dark.disabled),
+        to_rgba_string(config.color.light.disabled),
+        /* header */
+        config.size.item_vertical_padding,
+        config.size.item_horizontal_padding,
+        config.font.dark_description_font_size,
+        config.font.dark_description_font_size,
+        to_rgba_string(config.color.dark.description),
+        config.font.light_description_font_size,
+        to_rgba_string(config.color.light.description),
+    )
+}
+
+pub(crate) fn get_search_css(config: &Config) -> String {
+    format!(
+        r#"
+            separator#{SEARCH_FIELD_NAME} {{
+                background: none;
+                min-height: 0px;
+                margin: 0px;
+                padding: {}px {}px;
+            }}
+            #{DARK_WIDGET_NAME} label.{SEARCH_PATH_CLASS} {{
+                color: {};
+            }}
+            #{LIGHT_WIDGET_NAME} label.{SEARCH_PATH_CLASS} {{
+                color: {};
+            }}
+        "#,
+        config.size.item_vertical_padding,
+        config.size.item_horizontal_padding,
+        to_rgba_string(config.color.dark.accelerator),
+        to_rgba_string(config.color.light.accelerator),
     )
 }
 
@@ -0,0 +1,188 @@
+use super::theme::{detect_system_color_scheme_on, read_portal_color_scheme_async, ColorSchemeSource, SystemColorScheme};
+use gtk::{
+    gio::{Cancellable, DBusCallFlags, DBusConnection, DBusConnectionFlags, DBusNodeInfo},
+    glib::{MainContext, MainLoop, ToVariant, Variant},
+};
+use std::{
+    cell::Cell,
+    fs,
+    io::{BufRead, BufReader},
+    path::PathBuf,
+    process::{Child, Command, Stdio},
+    rc::Rc,
+    sync::mpsc,
+    thread,
+};
+
+const BUS_CONFIG: &str = r#"<!DOCTYPE busconfig PUBLIC "-//freedesktop//DTD D-Bus Bus Configuration 1.0//EN" "http://www.freedesktop.org/standards/dbus/1.0/busconfig.dtd">
+<busconfig>
+  <type>session</type>
+  <listen>unix:tmpdir=/tmp</listen>
+  <auth>EXTERNAL</auth>
+  <policy context="default">
+    <allow send_destination="*" eavesdrop="true"/>
+    <allow eavesdrop="true"/>
+    <allow own="*"/>
+  </policy>
+</busconfig>"#;
+
+const READ_ONE_METHOD: &str = r#"<method name="ReadOne"><arg type="s" name="namespace" direction="in"/><arg type="s" name="key" direction="in"/><arg type="v" name="value" direction="out"/></method>"#;
+const READ_METHOD: &str = r#"<method name="Read"><arg type="s" name="namespace" direction="in"/><arg type="s" name="key" direction="in"/><arg type="v" name="value" direction="out"/></method>"#;
+
+/// dbus-daemon serving only one test, killed on drop.
+struct PrivateBus {
+    daemon: Child,
+    config_path: PathBuf,
+    address: String,
+}
+
+impl PrivateBus {
+    /// Returns None when dbus-daemon is not installed.
+    fn start(name: &str) -> Option<Self> {
+        let config_path = std::env::temp_dir().join(format!("wcpopup-{}-{}.conf", name, std::process::id()));
+        fs::write(&config_path, BUS_CONFIG).unwrap();
+
+        let mut daemon = match Command::new("dbus-daemon").arg(format!("--config-file={}", config_path.display())).arg("--nofork").arg("--print-address").stdout(Stdio::piped()).spawn() {
+            Ok(daemon) => daemon,
+            Err(_) => {
+                let _ = fs::remove_file(&config_path);
+                eprintln!("dbus-daemon is not installed, skipped");
+                return None;
+            }
+        };
+
+        let mut address = String::new();
+        BufReader::new(daemon.stdout.take().unwrap()).read_line(&mut address).unwrap();
+
+        Some(Self {
+            daemon,
+            config_path,
+            address: address.trim().to_string(),
+        })
+    }
+
+    fn connect(&self) -> DBusConnection {
+        connect(&self.address)
+    }
+
+    /// Serves a stub org.freedesktop.portal.Settings on its own thread, answering color_scheme to the methods.
+    fn serve_portal(&self, methods: &[&str], color_scheme: u32) {
+        let address = self.address.clone();
+        let xml = format!(r#"<node><interface name="org.freedesktop.portal.Settings">{}</interface></node>"#, methods.concat());
+        let (ready_sender, ready_receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            let context = MainContext::new();
+            context
+                .with_thread_default(|| {
+                    let connection = connect(&address);
+                    let node = DBusNodeInfo::for_xml(&xml).unwrap();
+                    let interface = node.lookup_interface("org.freedesktop.portal.Settings").unwrap();
+                    connection
+                        .register_object(
+                            "/org/freedesktop/portal/desktop",
+                            &interface,
+                            move |_, _, _, _, method, _, invocation| {
+                                /* Read wraps the value in two variants, ReadOne in one */
+                                let value = if method == "Read" {
+                                    Variant::from_variant(&color_scheme.to_variant())
+                                } else {
+                                    color_scheme.to_variant()
+                                };
+                                invocation.return_value(Some(&(value,).to_variant()));
+                            },
+                            |_, _, _, _, _| unreachable!(),
+                            |_, _, _, _, _, _| false,
+                        )
+                        .unwrap();
+                    connection
+                        .call_sync(
+                            Some("org.freedesktop.DBus"),
+                            "/org/freedesktop/DBus",
+                            "org.freedesktop.DBus",
+                            "RequestName",
+                            Some(&("org.freedesktop.portal.Desktop", 0u32).to_variant()),
+                            None,
+                            DBusCallFlags::NONE,
+                            -1,
+                            None::<&Cancellable>,
+                        )
+                        .unwrap();
+                    ready_sender.send(()).unwrap();
+                    MainLoop::new(Some(&context), false).run();
+                })
+                .unwrap();
+        });
+
+        ready_receiver.recv().unwrap();
+    }
+}
+
+impl Drop for PrivateBus {
+    fn drop(&mut self) {
+        let _ = self.daemon.kill();
+        let _ = self.daemon.wait();
+        let _ = fs::remove_file(&self.config_path);
+    }
+}
+
+fn connect(address: &str) -> DBusConnection {
+    DBusConnection::for_address_sync(address, DBusConnectionFlags::AUTHENTICATION_CLIENT | DBusConnectionFlags::MESSAGE_BUS_CONNECTION, None, None::<&Cancellable>).unwrap()
+}
+
+#[test]
+fn detect_reads_color_scheme_from_portal() {
+    let Some(bus) = PrivateBus::start("read-one") else {
+        return;
+    };
+    bus.serve_portal(&[READ_ONE_METHOD, READ_METHOD], 1);
+
+    assert_eq!(
+        detect_system_color_scheme_on(&bus.connect()),
+        SystemColorScheme {
+            is_dark: true,
+            source: ColorSchemeSource::Portal
+        }
+    );
+}
+
+#[test]
+fn detect_falls_back_to_deprecated_read() {
+    let Some(bus) = PrivateBus::start("read") else {
+        return;
+    };
+    bus.serve_portal(&[READ_METHOD], 2);
+
+    assert_eq!(
+        detect_system_color_scheme_on(&bus.connect()),
+        SystemColorScheme {
+            is_dark: false,
+            source: ColorSchemeSource::Portal
+        }
+    );
+}
+
+#[test]
+fn async_read_calls_back_on_main_context() {
+    let Some(bus) = PrivateBus::start("async") else {
+        return;
+    };
+    bus.serve_portal(&[READ_ONE_METHOD, READ_METHOD], 1);
+    let connection = bus.connect();
+
+    let context = MainContext::new();
+    context
+        .with_thread_default(|| {
+            let result = Rc::new(Cell::new(None));
+            let callback_result = result.clone();
+            read_portal_color_scheme_async(&connection, move |portal_scheme| callback_result.set(Some(portal_scheme)));
+
+            /* The call returns before the reply, which arrives while iterating the context */
+            assert_eq!(result.get(), None);
+            while result.get().is_none() {
+                context.iteration(true);
+            }
+            assert_eq!(result.get(), Some(Some(true)));
+        })
+        .unwrap();
+}
@@ -0,0 +1,258 @@
+use super::{on_theme_change, to_gtk_menu};
+use crate::{MenuType, ThemeChangeFactor};
+use gtk::{
+    gio::{self, BusType, Cancellable, DBusCallFlags, DBusConnection, DBusSignalFlags},
+    glib::{ObjectExt, ToVariant, Variant},
+    prelude::{GtkSettingsExt, WidgetExt},
+};
+use std::sync::{Mutex, Once};
+
+const WATCH_DATA_KEY: &str = "wcpopup_theme_watch";
+
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const PORTAL_SETTINGS_INTERFACE: &str = "org.freedesktop.portal.Settings";
+const APPEARANCE_NAMESPACE: &str = "org.freedesktop.appearance";
+const COLOR_SCHEME_KEY: &str = "color-scheme";
+/* Give up on a portal that does not respond */
+const PORTAL_TIMEOUT_MSEC: i32 = 500;
+
+/// Source used to decide whether the desktop is dark.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorSchemeSource {
+    /// `org.freedesktop.appearance color-scheme` read from the settings portal.
+    Portal,
+    /// `gtk-application-prefer-dark-theme` of gtk::Settings.
+    PreferDarkTheme,
+    /// Whether `gtk-theme-name` contains "dark".
+    ThemeName,
+}
+
+/// Result of the system dark mode detection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SystemColorScheme {
+    pub is_dark: bool,
+    pub source: ColorSchemeSource,
+}
+
+/// Portal color scheme read off the build path and updated by SettingChanged. None until the first reply.
+static PORTAL_COLOR_SCHEME: Mutex<Option<bool>> = Mutex::new(None);
+static PORTAL_MONITOR: Once = Once::new();
+
+/// Detects whether the desktop prefers dark, asking the settings portal on the session bus first.
+/// This blocks until the portal replies or times out.
+///
+/// Falls back to `gtk-application-prefer-dark-theme`, and then to the theme name,
+/// when the portal is unavailable or reports no preference.
+pub fn detect_system_color_scheme() -> SystemColorScheme {
+    let portal_scheme = gio::bus_get_sync(BusType::Session, None::<&Cancellable>).ok().and_then(|connection| read_portal_color_scheme(&connection));
+    resolve_color_scheme(portal_scheme)
+}
+
+/// Same as [`detect_system_color_scheme`], but asks the portal on the specified connection.
+///
+/// Useful to run the detection against a private dbus-daemon serving a stub portal.
+pub fn detect_system_color_scheme_on(connection: &DBusConnection) -> SystemColorScheme {
+    resolve_color_scheme(read_portal_color_scheme(connection))
+}
+
+fn resolve_color_scheme(portal_scheme: Option<bool>) -> SystemColorScheme {
+    if let Some(is_dark) = portal_scheme {
+        return SystemColorScheme {
+            is_dark,
+            source: ColorSchemeSource::Portal,
+        };
+    }
+
+    if let Some(settings) = gtk::Settings::default() {
+        if settings.is_gtk_application_prefer_dark_theme() {
+            return SystemColorScheme {
+                is_dark: true,
+                source: ColorSchemeSource::PreferDarkTheme,
+            };
+        }
+
+        if let Some(theme_name) = settings.gtk_theme_name() {
+            return SystemColorScheme {
+                is_dark: theme_name.as_str().to_lowercase().contains("dark"),
+                source: ColorSchemeSource::ThemeName,
+            };
+        }
+    }
+
+    SystemColorScheme {
+        is_dark: false,
+        source: ColorSchemeSource::ThemeName,
+    }
+}
+
+/// Returns Some(true) for "prefer dark", Some(false) for "prefer light" and None for "no preference" or failure.
+fn read_portal_color_scheme(connection: &DBusConnection) -> Option<bool> {
+    let parameters = (APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY).to_variant();
+    /* ReadOne is available since version 2 of the interface, Read is deprecated but still widely served */
+    let value = call_portal(connection, "ReadOne", &parameters).or_else(|| call_portal(connection, "Read", &parameters))?;
+    to_prefers_dark(&value)
+}
+
+/// Same as read_portal_color_scheme, but calls back on the thread-default main context instead of blocking.
+pub(crate) fn read_portal_color_scheme_async<F: FnOnce(Option<bool>) + 'static>(connection: &DBusConnection, callback: F) {
+    let fallback_connection = connection.clone();
+    call_portal_async(connection, "ReadOne", move |value| match value {
+        Some(value) => callback(to_prefers_dark(&value)),
+        None => call_portal_async(&fallback_connection, "Read", move |value| callback(value.as_ref().and_then(to_prefers_dark))),
+    });
+}
+
+fn call_portal(connection: &DBusConnection, method: &str, parameters: &Variant) -> Option<Variant> {
+    let reply = connection
+        .call_sync(Some(PORTAL_BUS_NAME), PORTAL_OBJECT_PATH, PORTAL_SETTINGS_INTERFACE, method, Some(parameters), None, DBusCallFlags::NONE, PORTAL_TIMEOUT_MSEC, None::<&Cancellable>)
+        .ok()?;
+
+    first_child(&reply)
+}
+
+fn call_portal_async<F: FnOnce(Option<Variant>) + 'static>(connection: &DBusConnection, method: &str, callback: F) {
+    let parameters = (APPEARANCE_NAMESPACE, COLOR_SCHEME_KEY).to_variant();
+    connection.call(
+        Some(PORTAL_BUS_NAME),
+        PORTAL_OBJECT_PATH,
+        PORTAL_SETTINGS_INTERFACE,
+        method,
+        Some(&parameters),
+        None,
+        DBusCallFlags::NONE,
+        PORTAL_TIMEOUT_MSEC,
+        None::<&Cancellable>,
+        move |reply| callback(reply.ok().as_ref().and_then(first_child)),
+    );
+}
+
+fn first_child(reply: &Variant) -> Option<Variant> {
+    if reply.n_children() == 0 {
+        return None;
+    }
+
+    Some(reply.child_value(0))
+}
+
+fn to_prefers_dark(value: &Variant) -> Option<bool> {
+    /* Read wraps the value in two variants, ReadOne in one */
+    let mut value = value.clone();
+    while let Some(inner) = value.as_variant() {
+        value = inner;
+    }
+
+    match value.get::<u32>()? {
+        1 => Some(true),
+        2 => Some(false),
+        _ => None,
+    }
+}
+
+/// Resolves the color scheme without blocking. gtk::Settings is read as is, and the portal value comes from the monitor.
+pub(crate) fn current_system_color_scheme() -> SystemColorScheme {
+    monitor_portal_color_scheme();
+    let portal_scheme = *PORTAL_COLOR_SCHEME.lock().unwrap();
+    resolve_color_scheme(portal_scheme)
+}
+
+/// Reads the portal asynchronously once, and keeps the value up to date whether or not any menu watches the system theme.
+fn monitor_portal_color_scheme() {
+    PORTAL_MONITOR.call_once(|| {
+        gio::bus_get(BusType::Session, None::<&Cancellable>, |result| {
+            if let Ok(connection) = result {
+                subscribe_portal_color_scheme(&connection);
+                read_portal_color_scheme_async(&connection, update_portal_color_scheme);
+            }
+        });
+    });
+}
+
+fn subscribe_portal_color_scheme(connection: &DBusConnection) {
+    /* GNOME 42+ and KDE switch color-scheme without touching gtk::Settings */
+    connection.signal_subscribe(
+        Some(PORTAL_BUS_NAME),
+        Some(PORTAL_SETTINGS_INTERFACE),
+        Some("SettingChanged"),
+        Some(PORTAL_OBJECT_PATH),
+        Some(APPEARANCE_NAMESPACE),
+        DBusSignalFlags::NONE,
+        |_, _, _, _, _, parameters| {
+            if let Some((_, key, value)) = parameters.get::<(String, String, Variant)>() {
+                if key == COLOR_SCHEME_KEY {
+                    update_portal_color_scheme(to_prefers_dark(&value));
+                }
+            }
+        },
+    );
+}
+
+fn update_portal_color_scheme(portal_scheme: Option<bool>) {
+    *PORTAL_COLOR_SCHEME.lock().unwrap() = portal_scheme;
+    on_system_theme_change();
+}
+
+/// Main menus following the system theme, stored in gtk::Settings data.
+struct ThemeWatch {
+    gtk_menu_handles: Vec<isize>,
+    is_dark: bool,
+}
+
+/// Registers a main menu to be restyled when the desktop switches between dark and light.
+pub(crate) fn watch_system_theme(gtk_menu_handle: isize) {
+    let settings = match gtk::Settings::default() {
+        Some(settings) => settings,
+        None => return,
+    };
+
+    if let Some(mut watch) = unsafe { settings.data::<ThemeWatch>(WATCH_DATA_KEY) } {
+        unsafe { watch.as_mut() }.gtk_menu_handles.push(gtk_menu_handle);
+    } else {
+        let watch = ThemeWatch {
+            gtk_menu_handles: vec![gtk_menu_handle],
+            is_dark: current_system_color_scheme().is_dark,
+        };
+        unsafe { settings.set_data(WATCH_DATA_KEY, watch) };
+
+        /* Connect once, settings are shared by all menus on the default screen */
+        settings.connect_gtk_theme_name_notify(|_| on_system_theme_change());
+        settings.connect_gtk_application_prefer_dark_theme_notify(|_| on_system_theme_change());
+    }
+
+    let gtk_menu = to_gtk_menu(gtk_menu_handle);
+    gtk_menu.connect_destroy(move |_| {
+        unwatch_system_theme(gtk_menu_handle);
+    });
+}
+
+fn unwatch_system_theme(gtk_menu_handle: isize) {
+    if let Some(settings) = gtk::Settings::default() {
+        if let Some(mut watch) = unsafe { settings.data::<ThemeWatch>(WATCH_DATA_KEY) } {
+            unsafe { watch.as_mut() }.gtk_menu_handles.retain(|handle| *handle != gtk_menu_handle);
+        }
+    }
+}
+
+fn on_system_theme_change() {
+    let settings = match gtk::Settings::default() {
+        Some(settings) => settings,
+        None => return,
+    };
+
+    let watch = match unsafe { settings.data::<ThemeWatch>(WATCH_DATA_KEY) } {
+        Some(mut watch) => unsafe { watch.as_mut() },
+        None => return,
+    };
+
+    /* Several settings may change for a single switch, so respond only when dark/light actually flips */
+    let is_dark = current_system_color_scheme().is_dark;
+    if watch.is_dark == is_dark {
+        return;
+    }
+    watch.is_dark = is_dark;
+
+    /* Submenus are restyled from their main menu */
+    for gtk_menu_handle in watch.gtk_menu_handles.clone() {
+        on_theme_change(MenuType::Main, gtk_menu_handle, None, ThemeChangeFactor::SystemSetting);
+    }
+}
@@ -0,0 +1,132 @@
+use super::util::{get_main_menu_handle, get_menu_data_mut, get_menu_item_data, menu_item_children, to_gtk_menu};
+use crate::{mnemonic::parse_mnemonic, MenuItemType};
+use gtk::{
+    gdk::ModifierType,
+    glib::{Cast, Propagation},
+    prelude::{MenuShellExt, WidgetExt},
+    MenuDirectionType,
+};
+use std::time::{Duration, Instant};
+
+/// Prefix typed in the shown Menu.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TypeAheadState {
+    prefix: String,
+    last_typed: Option<Instant>,
+}
+
+impl TypeAheadState {
+    pub(crate) fn reset(&mut self) {
+        self.prefix.clear();
+        self.last_typed = None;
+    }
+}
+
+pub(crate) fn connect_type_ahead(gtk_menu: &gtk::Menu, gtk_menu_handle: isize) {
+    gtk_menu.connect_key_press_event(move |gtk_menu, event| {
+        let menu_data = get_menu_data_mut(get_main_menu_handle(gtk_menu_handle));
+        let Some(settings) = menu_data.config.type_ahead else {
+            return Propagation::Proceed;
+        };
+        let mnemonic = menu_data.config.mnemonic;
+
+        /* Typed characters go to the search field instead */
+        if menu_data.search.is_enabled() {
+            return Propagation::Proceed;
+        }
+
+        /* Keys with Ctrl, Alt or Super are left to accelerators */
+        if !menu_data.visible || event.state().intersects(ModifierType::CONTROL_MASK | ModifierType::MOD1_MASK | ModifierType::SUPER_MASK) {
+            return Propagation::Proceed;
+        }
+
+        let Some(c) = event.keyval().to_unicode().filter(|c| !c.is_control()) else {
+            return Propagation::Proceed;
+        };
+
+        let state = &mut menu_data.type_ahead;
+        if state.last_typed.is_some_and(|last_typed| last_typed.elapsed() > Duration::from_millis(settings.reset_ms)) {
+            state.reset();
+        }
+
+        /* Space activates the highlighted MenuItem unless typing */
+        if c == ' ' && state.prefix.is_empty() {
+            return Propagation::Proceed;
+        }
+
+        state.prefix.extend(c.to_lowercase());
+        state.last_typed = Some(Instant::now());
+
+        /* Repeating the same character cycles through the MenuItems starting with it */
+        let mut chars = state.prefix.chars();
+        let first = chars.next().unwrap_or_default();
+        let cycling = chars.all(|c| c == first);
+        let prefix = if cycling {
+            first.to_string()
+        } else {
+            state.prefix.clone()
+        };
+
+        let mut candidates = Vec::new();
+        collect_candidates(gtk_menu_handle, settings.search_submenus, &mut Vec::new(), &mut candidates);
+
+        /* A new search starts after the highlighted MenuItem, and a longer prefix keeps it if it still matches */
+        let current = gtk_menu.selected_item().and_then(|selected| candidates.iter().position(|(gtk_menu_item, _)| gtk_menu_item.upcast_ref::<gtk::Widget>() == &selected));
+        let start = match current {
+            Some(index) if cycling => index + 1,
+            Some(index) => index,
+            None => 0,
+        };
+
+        let count = candidates.len();
+        let found = (0..count).map(|offset| (start + offset) % count).find(|index| {
+            let label = &get_menu_item_data(&candidates[*index].0).label;
+            parse_mnemonic(label, mnemonic).text.to_lowercase().starts_with(&prefix)
+        });
+
+        if let Some(index) = found {
+            let (gtk_menu_item, ancestors) = &candidates[index];
+            select_item(gtk_menu_item, ancestors);
+        }
+
+        Propagation::Stop
+    });
+}
+
+/// Collects visible and enabled MenuItems with the submenu items they belong to.
+fn collect_candidates(gtk_menu_handle: isize, search_submenus: bool, ancestors: &mut Vec<gtk::MenuItem>, candidates: &mut Vec<(gtk::MenuItem, Vec<gtk::MenuItem>)>) {
+    for child in menu_item_children(&to_gtk_menu(gtk_menu_handle)) {
+        let Ok(gtk_menu_item) = child.downcast::<gtk::MenuItem>() else {
+            continue;
+        };
+
+        let item = get_menu_item_data(&gtk_menu_item);
+        if !item.visible || item.disabled || !item.menu_item_type.is_selectable() {
+            continue;
+        }
+
+        candidates.push((gtk_menu_item.clone(), ancestors.clone()));
+
+        if search_submenus && item.menu_item_type == MenuItemType::Submenu {
+            if let Some(submenu) = item.submenu.as_ref() {
+                ancestors.push(gtk_menu_item);
+                collect_candidates(submenu.gtk_menu_handle, search_submenus, ancestors, candidates);
+                ancestors.pop();
+            }
+        }
+    }
+}
+
+/// Highlights the MenuItem opening the submenus it belongs to.
+fn select_item(gtk_menu_item: &gtk::MenuItem, ancestors: &[gtk::MenuItem]) {
+    for ancestor in ancestors {
+        if let Some(gtk_menu_shell) = ancestor.parent().and_then(|parent| parent.downcast::<gtk::MenuShell>().ok()) {
+            gtk_menu_shell.select_item(ancestor);
+            gtk_menu_shell.emit_move_current(MenuDirectionType::Child);
+        }
+    }
+
+    if let Some(gtk_menu_shell) = gtk_menu_item.parent().and_then(|parent| parent.downcast::<gtk::MenuShell>().ok()) {
+        gtk_menu_shell.select_item(gtk_menu_item);
+    }
+}
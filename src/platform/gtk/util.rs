@@ -1,4 +1,4 @@
-use super::{MenuData, MenuItem};
+use super::{theme::current_system_color_scheme, MenuData, MenuItem};
 use crate::{
     config::{Config, FontWeight},
     MenuIconKind, MenuItemType,
@@ -9,7 +9,7 @@ use gtk::{
         translate::{FromGlibPtrNone, ToGlibPtr},
         IsA, ObjectExt,
     },
-    prelude::GtkSettingsExt,
+    prelude::ContainerExt,
     AccelGroup, Widget,
 };
 use std::path::Path;
@@ -24,6 +24,17 @@ pub(crate) fn get_menu_data_mut<'a>(gtk_menu_handle: isize) -> &'a mut MenuData
     unsafe { menu.data::<MenuData>("data").unwrap().as_mut() }
 }
 
+pub(crate) fn get_main_menu_handle(gtk_menu_handle: isize) -> isize {
+    let mut handle = gtk_menu_handle;
+    loop {
+        let parent_gtk_menu_handle = get_menu_data(handle).parent_gtk_menu_handle;
+        if parent_gtk_menu_handle <= 0 {
+            return handle;
+        }
+        handle = parent_gtk_menu_handle;
+    }
+}
+
 pub(crate) fn get_accel_group<'a>(gtk_menu_handle: isize) -> &'a AccelGroup {
     let menu = to_gtk_menu(gtk_menu_handle);
     unsafe { menu.data::<AccelGroup>("accel_group").unwrap().as_ref() }
@@ -37,6 +48,11 @@ pub(crate) fn get_menu_item_data_mut<'a>(gtk_menu_item: &impl IsA<Widget>) -> &'
     unsafe { gtk_menu_item.data::<MenuItem>("data").unwrap().as_mut() }
 }
 
+/// Children of gtk::Menu which hold a MenuItem. The search field and its matches are excluded.
+pub(crate) fn menu_item_children(gtk_menu: &gtk::Menu) -> Vec<Widget> {
+    gtk_menu.children().into_iter().filter(|child| unsafe { child.data::<MenuItem>("data").is_some() }).collect()
+}
+
 pub(crate) fn to_gtk_window(gtk_window_handle: isize) -> gtk::Window {
     let window: gtk::Window = unsafe { gtk::Window::from_glib_none(gtk_window_handle as *mut GtkWindow) };
     window
@@ -78,12 +94,7 @@ pub(crate) fn to_font_weight(weight: FontWeight) -> u32 {
 }
 
 pub(crate) fn is_sys_dark() -> bool {
-    if let Some(settings) = gtk::Settings::default() {
-        if let Some(theme_name) = settings.gtk_theme_name() {
-            return theme_name.as_str().to_lowercase().contains("dark");
-        }
-    }
-    false
+    current_system_color_scheme().is_dark
 }
 
 pub(crate) fn get_custom_check_width(config: &Config) -> Option<u32> {
@@ -0,0 +1,199 @@
+use super::{register_menu_item, with_menu_data};
+use crate::{
+    config::{Config, IconSettings, SearchSettings, Theme},
+    validation::{validate_accelerator_conflicts, validate_config, validate_items},
+    Error, Menu, MenuIcon, MenuItem, MenuType,
+};
+use std::collections::{HashMap, HashSet};
+
+#[derive(Debug)]
+/// Builder to create Menu.
+pub struct MenuBuilder {
+    menu: Menu,
+    items: Vec<MenuItem>,
+    config: Config,
+}
+
+impl MenuBuilder {
+    /// Creates a new Menu for the specified window handle.
+    /// The handle is only kept for compatibility, so any value can be used.
+    pub fn new(window_handle: isize) -> Self {
+        Self::new_from_config(window_handle, Config::default())
+    }
+
+    /// Creates a new Menu with the specified Theme for the specified window handle.
+    pub fn new_with_theme(window_handle: isize, theme: Theme) -> Self {
+        let config = Config {
+            theme,
+            ..Default::default()
+        };
+        Self::new_from_config(window_handle, config)
+    }
+
+    /// Creates a new Menu using the specified Config for the specified window handle.
+    pub fn new_from_config(window_handle: isize, config: Config) -> Self {
+        let config = Config {
+            icon: if let Some(icon) = config.icon {
+                Some(icon)
+            } else {
+                Some(IconSettings::default())
+            },
+            ..config
+        };
+        let menu = Menu::new(window_handle, None, &config);
+        Self {
+            menu,
+            config,
+            items: Vec::new(),
+        }
+    }
+
+    /// Adds a text MenuItem to Menu.
+    pub fn text(&mut self, id: &str, label: &str, disabled: bool) -> &Self {
+        self.append(MenuItem::new_text_item(id, label, None, disabled, None))
+    }
+
+    /// Adds a text MenuItem with accelerator to Menu.
+    pub fn text_with_accelerator(&mut self, id: &str, label: &str, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        self.append(MenuItem::new_text_item(id, label, Some(accelerator.as_str()), disabled, None))
+    }
+
+    /// Adds a text MenuItem with icon to Menu.
+    pub fn text_with_icon(&mut self, id: &str, label: &str, disabled: bool, icon: MenuIcon) -> &Self {
+        self.append(MenuItem::new_text_item(id, label, None, disabled, Some(icon)))
+    }
+
+    /// Adds a text MenuItem with accelerator and icon to Menu.
+    pub fn text_with_accel_icon(&mut self, id: &str, label: &str, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        self.append(MenuItem::new_text_item(id, label, Some(accelerator.as_str()), disabled, Some(icon)))
+    }
+
+    /// Adds a check MenuItem to Menu.
+    pub fn check(&mut self, id: &str, label: &str, checked: bool, disabled: bool) -> &Self {
+        self.append(MenuItem::new_check_item(id, label, None, checked, disabled, None))
+    }
+
+    /// Adds a check MenuItem with accelerator to Menu.
+    pub fn check_with_accelerator(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        self.append(MenuItem::new_check_item(id, label, Some(accelerator.as_str()), checked, disabled, None))
+    }
+
+    /// Adds a check MenuItem with icon to Menu.
+    pub fn check_with_icon(&mut self, id: &str, label: &str, checked: bool, disabled: bool, icon: MenuIcon) -> &Self {
+        self.append(MenuItem::new_check_item(id, label, None, checked, disabled, Some(icon)))
+    }
+
+    /// Adds a check MenuItem with accelerator and icon to Menu.
+    pub fn check_with_accel_icon(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        self.append(MenuItem::new_check_item(id, label, Some(accelerator.as_str()), checked, disabled, Some(icon)))
+    }
+
+    /// Adds a radio MenuItem to Menu.
+    pub fn radio(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool) -> &Self {
+        self.append(MenuItem::new_radio_item(id, label, name, None, checked, disabled, None))
+    }
+
+    /// Adds a radio MenuItem with accelerator to Menu.
+    pub fn radio_with_accelerator(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        self.append(MenuItem::new_radio_item(id, label, name, Some(accelerator.as_str()), checked, disabled, None))
+    }
+
+    /// Adds a radio MenuItem with icon to Menu.
+    pub fn radio_with_icon(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, icon: MenuIcon) -> &Self {
+        self.append(MenuItem::new_radio_item(id, label, name, None, checked, disabled, Some(icon)))
+    }
+
+    /// Adds a radio MenuItem with accelerator and icon to Menu.
+    #[allow(clippy::too_many_arguments)]
+    pub fn radio_with_accel_icon(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        self.append(MenuItem::new_radio_item(id, label, name, Some(accelerator.as_str()), checked, disabled, Some(icon)))
+    }
+
+    /// Adds a separator to Menu.
+    pub fn separator(&mut self) -> &Self {
+        self.append(MenuItem::new_separator())
+    }
+
+    pub fn separator_with_id(&mut self, id: &str) -> &Self {
+        self.append(MenuItem::new_separator_with_id(id))
+    }
+
+    /// Adds a section heading to Menu. It cannot be selected.
+    pub fn header(&mut self, label: &str) -> &Self {
+        self.append(MenuItem::new_header(label))
+    }
+
+    pub fn header_with_id(&mut self, id: &str, label: &str) -> &Self {
+        self.append(MenuItem::new_header_with_id(id, label))
+    }
+
+    /// Adds a submenu MenuItem to Menu.
+    pub fn submenu(&mut self, id: &str, label: &str, disabled: bool) -> Self {
+        self.new_submenu(MenuItem::new_submenu_item(id, label, disabled, None))
+    }
+
+    pub fn submenu_with_icon(&mut self, id: &str, label: &str, disabled: bool, icon: MenuIcon) -> Self {
+        self.new_submenu(MenuItem::new_submenu_item(id, label, disabled, Some(icon)))
+    }
+
+    fn new_submenu(&mut self, mut item: MenuItem) -> Self {
+        let menu = Menu::new(0, Some(&self.menu), &self.config);
+        item.items = None;
+        item.submenu = Some(menu.clone());
+        item.menu_handle = self.menu.menu_handle;
+        self.items.push(item);
+
+        MenuBuilder {
+            menu,
+            config: self.config.clone(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Adds a MenuItem to MenuBuilder.
+    pub fn append(&mut self, mut menu_item: MenuItem) -> &Self {
+        register_menu_item(&self.menu, &mut menu_item, &self.config);
+        self.items.push(menu_item);
+        self
+    }
+
+    /// Adds MenuItems to MenuBuilder.
+    pub fn append_all(&mut self, menu_items: Vec<MenuItem>) -> &Self {
+        for menu_item in menu_items {
+            self.append(menu_item);
+        }
+        self
+    }
+
+    /// Adds a search field at the top of Menu to find MenuItems in Menu and its submenus. Only for the main Menu.
+    pub fn search(&mut self, settings: SearchSettings) -> &Self {
+        self.config.search = Some(settings);
+        self
+    }
+
+    /// Build Menu to make it ready to become visible.
+    /// Must call this function before showing Menu, otherwise nothing shows up.
+    pub fn build(self) -> Result<Menu, Error> {
+        /* Submenus are validated as part of the main menu */
+        let mut warnings = Vec::new();
+        if self.menu.menu_type == MenuType::Main {
+            validate_config(&self.config)?;
+            validate_items(&self.items, &mut HashSet::new())?;
+            validate_accelerator_conflicts(&self.items, &mut HashMap::new(), self.config.accelerator_conflict, &mut warnings)?;
+        }
+
+        with_menu_data(self.menu.menu_handle, |data| {
+            data.config = self.config;
+            data.items = self.items;
+            data.accelerator_conflicts = warnings;
+        });
+
+        Ok(self.menu)
+    }
+}
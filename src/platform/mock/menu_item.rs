@@ -0,0 +1,319 @@
+use super::{update_item, Menu};
+use crate::{validation::validate_icon, CheckState, Error, MenuIcon, MenuItemType};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU16, Ordering};
+
+static UUID: AtomicU16 = AtomicU16::new(0);
+
+/// Menu item.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MenuItem {
+    pub id: String,
+    pub label: String,
+    pub accelerator: String,
+    pub name: String,
+    pub menu_item_type: MenuItemType,
+    pub submenu: Option<Menu>,
+    /// Check state of a checkbox or radio. Mixed is only for checkbox.
+    #[serde(default)]
+    pub check_state: CheckState,
+    pub disabled: bool,
+    pub visible: bool,
+    pub icon: Option<MenuIcon>,
+    /// Extra words matched by search besides the label.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Text shown on hover, also for disabled MenuItems.
+    #[serde(default)]
+    pub tooltip: Option<String>,
+    /// Second line shown under the label in smaller and dimmer text.
+    #[serde(default)]
+    pub description: Option<String>,
+    pub uuid: u16,
+    pub(crate) items: Option<Vec<MenuItem>>,
+    pub(crate) menu_handle: isize,
+}
+
+impl MenuItem {
+    pub(crate) fn new(menu_item_type: MenuItemType) -> Self {
+        Self {
+            id: String::new(),
+            label: String::new(),
+            accelerator: String::new(),
+            name: String::new(),
+            menu_item_type,
+            submenu: None,
+            uuid: UUID.fetch_add(1, Ordering::Relaxed),
+            menu_handle: 0,
+            check_state: CheckState::Unchecked,
+            disabled: false,
+            visible: true,
+            items: if menu_item_type == MenuItemType::Submenu {
+                Some(Vec::new())
+            } else {
+                None
+            },
+            icon: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
+        }
+    }
+
+    pub fn set_label(&mut self, label: &str) {
+        self.label = label.to_string();
+        update_item(self.menu_handle, self.uuid, |item| item.label = label.to_string());
+    }
+
+    /// Sets the words matched by search besides the label.
+    pub fn set_keywords(&mut self, keywords: &[&str]) {
+        self.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+        let keywords = self.keywords.clone();
+        update_item(self.menu_handle, self.uuid, |item| item.keywords = keywords);
+    }
+
+    /// Sets the second line shown under the label. None removes it.
+    pub fn set_description(&mut self, description: Option<&str>) {
+        self.description = description.map(str::to_string);
+        let description = self.description.clone();
+        update_item(self.menu_handle, self.uuid, |item| item.description = description);
+    }
+
+    /// Sets the text shown on hover. A disabled MenuItem shows it as well, for example to explain why it is disabled.
+    pub fn set_tooltip(&mut self, tooltip: Option<&str>) {
+        self.tooltip = tooltip.map(str::to_string);
+        let tooltip = self.tooltip.clone();
+        update_item(self.menu_handle, self.uuid, |item| item.tooltip = tooltip);
+    }
+
+    pub fn set_disabled(&mut self, disabled: bool) {
+        self.disabled = disabled;
+        update_item(self.menu_handle, self.uuid, |item| item.disabled = disabled);
+    }
+
+    pub fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+        update_item(self.menu_handle, self.uuid, |item| item.visible = visible);
+    }
+
+    pub fn set_icon(&mut self, icon: Option<MenuIcon>) -> Result<(), Error> {
+        if !self.menu_item_type.is_selectable() {
+            return Ok(());
+        }
+
+        if let Some(icon) = &icon {
+            validate_icon(icon)?;
+        }
+
+        self.icon = icon;
+        let icon = self.icon.clone();
+        update_item(self.menu_handle, self.uuid, |item| item.icon = icon);
+        Ok(())
+    }
+}
+
+impl MenuItem {
+    pub fn new_text_item(id: &str, label: &str, accelerator: Option<&str>, disabled: bool, icon: Option<MenuIcon>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            accelerator: accelerator.unwrap_or_default().to_string(),
+            disabled,
+            icon,
+            ..MenuItem::new(MenuItemType::Text)
+        }
+    }
+}
+
+impl MenuItem {
+    pub fn new_check_item(id: &str, label: &str, accelerator: Option<&str>, checked: bool, disabled: bool, icon: Option<MenuIcon>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            accelerator: accelerator.unwrap_or_default().to_string(),
+            check_state: checked.into(),
+            disabled,
+            icon,
+            ..MenuItem::new(MenuItemType::Checkbox)
+        }
+    }
+
+    pub fn new_radio_item(id: &str, label: &str, name: &str, accelerator: Option<&str>, checked: bool, disabled: bool, icon: Option<MenuIcon>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            accelerator: accelerator.unwrap_or_default().to_string(),
+            name: name.to_string(),
+            check_state: checked.into(),
+            disabled,
+            icon,
+            ..MenuItem::new(MenuItemType::Radio)
+        }
+    }
+
+    pub fn set_checked(&mut self, checked: bool) {
+        self.set_check_state(checked.into());
+    }
+
+    /// Sets the check state. Mixed is only for checkbox.
+    pub fn set_check_state(&mut self, check_state: CheckState) {
+        if !matches!(self.menu_item_type, MenuItemType::Checkbox | MenuItemType::Radio) {
+            return;
+        }
+
+        if check_state == CheckState::Mixed && self.menu_item_type != MenuItemType::Checkbox {
+            return;
+        }
+
+        self.check_state = check_state;
+
+        /* Exit if menu is not built */
+        if self.menu_handle == 0 {
+            return;
+        }
+
+        if self.menu_item_type == MenuItemType::Radio && check_state.is_checked() {
+            super::check_radio(self.menu_handle, self.uuid);
+        } else {
+            update_item(self.menu_handle, self.uuid, |item| item.check_state = check_state);
+        }
+    }
+}
+
+impl MenuItem {
+    pub fn new_submenu_item(id: &str, label: &str, disabled: bool, icon: Option<MenuIcon>) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            disabled,
+            icon,
+            ..MenuItem::new(MenuItemType::Submenu)
+        }
+    }
+
+    /// Adds a menu item to submenu.
+    pub fn add_menu_item(&mut self, item: MenuItem) -> &Self {
+        if let Some(items) = self.items.as_mut() {
+            items.push(item);
+        }
+        self
+    }
+}
+
+impl MenuItem {
+    pub fn new_separator() -> Self {
+        MenuItem::new(MenuItemType::Separator)
+    }
+
+    pub fn new_separator_with_id(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            ..MenuItem::new(MenuItemType::Separator)
+        }
+    }
+
+    pub fn new_header(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            ..MenuItem::new(MenuItemType::Header)
+        }
+    }
+
+    pub fn new_header_with_id(id: &str, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            ..MenuItem::new(MenuItemType::Header)
+        }
+    }
+}
+
+impl MenuItem {
+    pub fn builder(menu_item_type: MenuItemType) -> MenuItemBuilder {
+        MenuItemBuilder {
+            menu_item: MenuItem::new(menu_item_type),
+        }
+    }
+}
+
+pub struct MenuItemBuilder {
+    menu_item: MenuItem,
+}
+
+impl MenuItemBuilder {
+    pub fn id(mut self, id: &str) -> Self {
+        self.menu_item.id = id.to_string();
+        self
+    }
+
+    pub fn label(mut self, label: &str) -> Self {
+        self.menu_item.label = label.to_string();
+        self
+    }
+
+    pub fn accelerator(mut self, accelerator: impl Into<String>) -> Self {
+        self.menu_item.accelerator = accelerator.into();
+        self
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.menu_item.name = name.to_string();
+        self
+    }
+
+    pub fn submenu(mut self, items: Vec<MenuItem>) -> Self {
+        if self.menu_item.menu_item_type == MenuItemType::Submenu {
+            self.menu_item.items = Some(items);
+        }
+        self
+    }
+
+    pub fn checked(mut self, checked: bool) -> Self {
+        self.menu_item.check_state = checked.into();
+        self
+    }
+
+    /// Check state of a checkbox including the mixed state.
+    pub fn check_state(mut self, check_state: CheckState) -> Self {
+        self.menu_item.check_state = check_state;
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.menu_item.disabled = disabled;
+        self
+    }
+
+    pub fn visible(mut self, visible: bool) -> Self {
+        self.menu_item.visible = visible;
+        self
+    }
+
+    pub fn icon(mut self, icon: MenuIcon) -> Self {
+        self.menu_item.icon = Some(icon);
+        self
+    }
+
+    /// Words matched by search besides the label.
+    pub fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.menu_item.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+        self
+    }
+
+    /// Text shown on hover, also for disabled MenuItems.
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.menu_item.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    /// Second line shown under the label in smaller and dimmer text.
+    pub fn description(mut self, description: &str) -> Self {
+        self.menu_item.description = Some(description.to_string());
+        self
+    }
+
+    /// Build the [`MenuItem`].
+    pub fn build(self) -> MenuItem {
+        self.menu_item
+    }
+}
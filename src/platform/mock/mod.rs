@@ -0,0 +1,567 @@
+mod builder;
+mod menu_item;
+#[cfg(all(test, feature = "mock"))]
+mod tests;
+#[cfg(feature = "accelerator")]
+use crate::{
+    accelerator::{ChordState, ChordStep},
+    Accelerator, AcceleratorChord,
+};
+use crate::{
+    config::*,
+    mnemonic::parse_mnemonic,
+    popup_channel,
+    search::search_items,
+    validation::{collect_ids, validate_items, validate_new_accelerators},
+    AcceleratorConflict, ActivationSource, CheckState, CloseReason, DismissReason, Error, MenuEvent, MenuItemType, MenuType, PopupResult, PopupSender,
+};
+pub use builder::*;
+pub use menu_item::*;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicIsize, Ordering},
+        LazyLock, Mutex,
+    },
+};
+
+static MENUS: LazyLock<Mutex<HashMap<isize, MenuData>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+static MENU_HANDLE: AtomicIsize = AtomicIsize::new(1);
+
+#[derive(Debug, Clone)]
+pub(crate) struct MenuData {
+    pub(crate) config: Config,
+    pub(crate) items: Vec<MenuItem>,
+    pub(crate) visible: bool,
+    pub(crate) popup_sender: Option<PopupSender>,
+    pub(crate) parent_menu_handle: isize,
+    /* Conflicts found under AcceleratorConflictPolicy::Warn, kept in the main menu */
+    pub(crate) accelerator_conflicts: Vec<AcceleratorConflict>,
+    #[cfg(feature = "accelerator")]
+    pub(crate) window_shortcuts: bool,
+    #[cfg(feature = "accelerator")]
+    pub(crate) chord_state: ChordState,
+}
+
+/// Menu without any window system. Keeps items and states in memory so that menu logic can be tested headlessly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Menu {
+    pub menu_handle: isize,
+    pub menu_type: MenuType,
+    parent_menu_handle: isize,
+    window_handle: isize,
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self {
+            menu_handle: 0,
+            parent_menu_handle: 0,
+            window_handle: 0,
+            menu_type: MenuType::Main,
+        }
+    }
+}
+
+impl Menu {
+    pub(crate) fn new(window_handle: isize, parent: Option<&Menu>, config: &Config) -> Self {
+        let menu_handle = MENU_HANDLE.fetch_add(1, Ordering::Relaxed);
+        let (parent_menu_handle, window_handle, menu_type) = match parent {
+            Some(parent) => (parent.menu_handle, parent.window_handle, MenuType::Submenu),
+            None => (0, window_handle, MenuType::Main),
+        };
+
+        let data = MenuData {
+            config: config.clone(),
+            items: Vec::new(),
+            visible: false,
+            popup_sender: None,
+            parent_menu_handle,
+            accelerator_conflicts: Vec::new(),
+            #[cfg(feature = "accelerator")]
+            window_shortcuts: false,
+            #[cfg(feature = "accelerator")]
+            chord_state: ChordState::default(),
+        };
+        MENUS.lock().unwrap().insert(menu_handle, data);
+
+        Self {
+            menu_handle,
+            menu_type,
+            parent_menu_handle,
+            window_handle,
+        }
+    }
+
+    pub(crate) fn main_menu_handle(&self) -> isize {
+        get_main_menu_handle(self.menu_handle)
+    }
+
+    pub fn config(&self) -> Config {
+        with_menu_data(self.menu_handle, |data| data.config.clone())
+    }
+
+    pub fn theme(&self) -> Theme {
+        with_menu_data(self.menu_handle, |data| data.config.theme)
+    }
+
+    /// Sets the theme for Menu.
+    pub fn set_theme(&self, theme: Theme) {
+        for menu_handle in collect_menu_handles(self.menu_handle) {
+            with_menu_data(menu_handle, |data| data.config.theme = theme);
+        }
+    }
+
+    /// Gets all MenuItems of Menu.
+    pub fn items(&self) -> Vec<MenuItem> {
+        with_menu_data(self.menu_handle, |data| data.items.clone())
+    }
+
+    /// Gets the MenuItem with the specified id.
+    pub fn get_menu_item_by_id(&self, id: &str) -> Option<MenuItem> {
+        find_by_id(self.menu_handle, id, false)
+    }
+
+    /// Adds a MenuItem to the end of MenuItems.
+    pub fn append(&mut self, item: MenuItem) -> Result<(), Error> {
+        self.add_item(item, None)
+    }
+
+    /// Adds a MenuItem at the specified index.
+    pub fn insert(&mut self, item: MenuItem, index: u32) -> Result<(), Error> {
+        self.add_item(item, Some(index as usize))
+    }
+
+    fn add_item(&mut self, mut item: MenuItem, index: Option<usize>) -> Result<(), Error> {
+        let mut ids = HashSet::new();
+        let main_menu_items = with_menu_data(self.main_menu_handle(), |data| data.items.clone());
+        collect_ids(&main_menu_items, &mut ids);
+        validate_items(std::slice::from_ref(&item), &mut ids)?;
+
+        let config = self.config();
+        let mut warnings = Vec::new();
+        validate_new_accelerators(&main_menu_items, &item, config.accelerator_conflict, &mut warnings)?;
+        with_menu_data(self.main_menu_handle(), |data| data.accelerator_conflicts.extend(warnings));
+        register_menu_item(self, &mut item, &config);
+
+        let uuid = item.uuid;
+        let check_radio_item = item.menu_item_type == MenuItemType::Radio && item.check_state.is_checked();
+
+        with_menu_data(self.menu_handle, |data| {
+            let index = index.unwrap_or(data.items.len()).min(data.items.len());
+            data.items.insert(index, item);
+        });
+
+        /* New checked radio item takes over the check from its group */
+        if check_radio_item {
+            check_radio(self.menu_handle, uuid);
+        }
+
+        Ok(())
+    }
+
+    /// Removes the MenuItem at the specified index.
+    pub fn remove_at(&mut self, index: u32) {
+        let removed_item = with_menu_data(self.menu_handle, |data| {
+            if (index as usize) < data.items.len() {
+                Some(data.items.remove(index as usize))
+            } else {
+                None
+            }
+        });
+
+        if let Some(submenu) = removed_item.and_then(|item| item.submenu) {
+            remove_menu_data(submenu.menu_handle);
+        }
+    }
+
+    /// Removes the MenuItem.
+    pub fn remove(&mut self, item: &MenuItem) {
+        let maybe_index = with_menu_data(self.menu_handle, |data| data.items.iter().position(|existing_item| existing_item.uuid == item.uuid));
+
+        if let Some(index) = maybe_index {
+            self.remove_at(index as u32);
+        }
+    }
+
+    /// Returns the accelerator conflicts found under AcceleratorConflictPolicy::Warn and clears them.
+    pub fn take_accelerator_conflicts(&self) -> Vec<AcceleratorConflict> {
+        with_menu_data(self.main_menu_handle(), |data| std::mem::take(&mut data.accelerator_conflicts))
+    }
+
+    /// Shows Menu at the specified point.
+    pub fn popup_at(&self, _x: i32, _y: i32) {
+        show_popup(self.menu_handle, None);
+    }
+
+    /// Shows Menu asynchronously at the specified point and returns how the popup ended.
+    pub async fn popup_at_async(&self, _x: i32, _y: i32) -> PopupResult {
+        let (tx, rx) = popup_channel();
+        show_popup(self.menu_handle, Some(tx));
+
+        /* The sender is dropped without result only when Menu is gone */
+        rx.recv().await.unwrap_or(PopupResult::Dismissed(DismissReason::Destroyed))
+    }
+}
+
+impl Menu {
+    /// Returns true while the popup is shown.
+    pub fn is_open(&self) -> bool {
+        with_menu_data(self.main_menu_handle(), |data| data.visible)
+    }
+
+    /// Simulates a user selecting the MenuItem with the specified id.
+    /// Returns false if the popup is not shown or the MenuItem cannot be selected.
+    pub fn activate_by_id(&self, id: &str) -> bool {
+        let main_menu_handle = self.main_menu_handle();
+        if !self.is_open() {
+            return false;
+        }
+
+        let Some(item) = find_by_id(main_menu_handle, id, true) else {
+            return false;
+        };
+
+        if item.disabled || !item.menu_item_type.is_selectable() || item.menu_item_type == MenuItemType::Submenu {
+            return false;
+        }
+
+        let item = activate_item(main_menu_handle, item, ActivationSource::Program);
+        finish_popup(main_menu_handle, PopupResult::Selected(item));
+
+        true
+    }
+
+    /// Simulates the pointer moving onto the MenuItem with the specified id.
+    /// Returns false if the popup is not shown or the MenuItem cannot be highlighted.
+    pub fn highlight(&self, id: &str) -> bool {
+        let main_menu_handle = self.main_menu_handle();
+        if !self.is_open() {
+            return false;
+        }
+
+        match find_by_id(main_menu_handle, id, true) {
+            Some(item) if !item.disabled && item.menu_item_type.is_selectable() => {
+                MenuEvent::send(main_menu_handle, MenuEvent::Highlighted(item));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Simulates a user pressing the mnemonic key of a MenuItem in the shown Menu.
+    /// A submenu item opens its submenu, which is notified as MenuEvent::SubmenuOpened. Other MenuItems are selected as a click.
+    /// Returns false if the popup is not shown or no visible and enabled MenuItem has the mnemonic. Mnemonics are enabled by Config.mnemonic.
+    pub fn press_mnemonic(&self, key: char) -> bool {
+        let main_menu_handle = self.main_menu_handle();
+        if !self.is_open() {
+            return false;
+        }
+
+        let key = key.to_lowercase().next();
+        let (items, mnemonic) = with_menu_data(main_menu_handle, |data| (data.items.clone(), data.config.mnemonic));
+        let Some(item) = items.into_iter().find(|item| item.visible && !item.disabled && item.menu_item_type.is_selectable() && parse_mnemonic(&item.label, mnemonic).key() == key) else {
+            return false;
+        };
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            MenuEvent::send(main_menu_handle, MenuEvent::Highlighted(item.clone()));
+            MenuEvent::send(main_menu_handle, MenuEvent::SubmenuOpened(item));
+            return true;
+        }
+
+        let item = activate_item(main_menu_handle, item, ActivationSource::Menu);
+        finish_popup(main_menu_handle, PopupResult::Selected(item));
+
+        true
+    }
+
+    /// Simulates a user typing the query in the search field and pressing Enter, which selects the top match as a click.
+    /// Returns false if the popup is not shown, the search field is not enabled or nothing matches.
+    pub fn submit_search(&self, query: &str) -> bool {
+        let main_menu_handle = self.main_menu_handle();
+        if !self.is_open() || with_menu_data(main_menu_handle, |data| data.config.search.is_none()) {
+            return false;
+        }
+
+        let (items, mnemonic) = with_menu_data(main_menu_handle, |data| (data.items.clone(), data.config.mnemonic));
+        let Some(top_match) = search_items(&items, query, mnemonic, 1).into_iter().next() else {
+            return false;
+        };
+
+        let item = activate_item(main_menu_handle, top_match.item, ActivationSource::Menu);
+        finish_popup(main_menu_handle, PopupResult::Selected(item));
+
+        true
+    }
+
+    /// Simulates the Escape key closing the popup.
+    pub fn dismiss(&self) {
+        self.dismiss_with(DismissReason::Escape);
+    }
+
+    /// Closes the popup and its submenus. A pending popup is resolved as [`DismissReason::Closed`].
+    pub fn close(&self) {
+        self.dismiss_with(DismissReason::Closed);
+    }
+
+    /// Binds the accelerators of MenuItems as window shortcuts which work while Menu is closed.
+    #[cfg(feature = "accelerator")]
+    pub fn set_window_shortcuts(&self, enabled: bool) {
+        with_menu_data(self.main_menu_handle(), |data| data.window_shortcuts = enabled);
+    }
+
+    /// Simulates a user pressing the accelerator on the window. Press each key of a chord like "Ctrl+K Ctrl+C" one by one.
+    /// While the popup is shown, the MenuItem is selected as PopupResult::Accelerator.
+    /// Otherwise it is activated as a window shortcut if enabled by Menu.set_window_shortcuts().
+    /// Returns false if the key is not handled, that is, no visible and enabled MenuItem has the accelerator or a chord starting with it.
+    #[cfg(feature = "accelerator")]
+    pub fn press_accelerator(&self, accelerator: &str) -> bool {
+        let Ok(accelerator) = accelerator.parse::<Accelerator>() else {
+            return false;
+        };
+
+        let main_menu_handle = self.main_menu_handle();
+        let (visible, window_shortcuts) = with_menu_data(main_menu_handle, |data| (data.visible, data.window_shortcuts));
+        if !visible && !window_shortcuts {
+            return false;
+        }
+
+        let mut chords = Vec::new();
+        collect_chords(main_menu_handle, &mut chords);
+        let step = with_menu_data(main_menu_handle, |data| data.chord_state.press(|chord_accelerator| *chord_accelerator == accelerator, chords));
+
+        let item = match step {
+            ChordStep::Matched(item) => item,
+            ChordStep::Pending | ChordStep::Cancelled => return true,
+            ChordStep::Unmatched => return false,
+        };
+
+        if visible {
+            let item = activate_item(main_menu_handle, item, ActivationSource::Accelerator);
+            finish_popup(main_menu_handle, PopupResult::Accelerator(item));
+        } else {
+            activate_item(main_menu_handle, item, ActivationSource::Shortcut);
+        }
+
+        true
+    }
+
+    /// Simulates the popup closing without selection for the specified reason.
+    pub fn dismiss_with(&self, reason: DismissReason) {
+        let main_menu_handle = self.main_menu_handle();
+        if self.is_open() {
+            finish_popup(main_menu_handle, PopupResult::Dismissed(reason));
+        }
+    }
+}
+
+fn show_popup(menu_handle: isize, sender: Option<PopupSender>) {
+    /* Finish the current popup so that its result never leaks into the new one */
+    if with_menu_data(menu_handle, |data| data.visible) {
+        finish_popup(menu_handle, PopupResult::Dismissed(DismissReason::Replaced));
+    }
+
+    with_menu_data(menu_handle, |data| {
+        data.visible = true;
+        data.popup_sender = sender;
+        #[cfg(feature = "accelerator")]
+        data.chord_state.reset();
+    });
+
+    MenuEvent::send(menu_handle, MenuEvent::Opened);
+}
+
+fn finish_popup(menu_handle: isize, result: PopupResult) {
+    let sender = with_menu_data(menu_handle, |data| {
+        data.visible = false;
+        data.popup_sender.take()
+    });
+
+    MenuEvent::send(
+        menu_handle,
+        MenuEvent::Closed {
+            reason: CloseReason::from(&result),
+        },
+    );
+
+    if let Some(sender) = sender {
+        let _ = sender.try_send(result);
+    }
+}
+
+/* Never call MenuEvent::send inside, since handlers may access the menu again */
+pub(crate) fn with_menu_data<R>(menu_handle: isize, f: impl FnOnce(&mut MenuData) -> R) -> R {
+    let mut menus = MENUS.lock().unwrap();
+    let data = menus.get_mut(&menu_handle).expect("Menu is not built");
+    f(data)
+}
+
+pub(crate) fn get_main_menu_handle(menu_handle: isize) -> isize {
+    let mut main_menu_handle = menu_handle;
+    loop {
+        let parent_menu_handle = with_menu_data(main_menu_handle, |data| data.parent_menu_handle);
+        if parent_menu_handle == 0 {
+            return main_menu_handle;
+        }
+        main_menu_handle = parent_menu_handle;
+    }
+}
+
+pub(crate) fn register_menu_item(menu: &Menu, item: &mut MenuItem, config: &Config) {
+    item.menu_handle = menu.menu_handle;
+
+    if item.menu_item_type != MenuItemType::Submenu || item.submenu.is_some() {
+        return;
+    }
+
+    let submenu = Menu::new(menu.window_handle, Some(menu), config);
+    let mut items = item.items.take().unwrap_or_default();
+    for submenu_item in items.iter_mut() {
+        register_menu_item(&submenu, submenu_item, config);
+    }
+    with_menu_data(submenu.menu_handle, |data| data.items = items);
+
+    item.submenu = Some(submenu);
+}
+
+pub(crate) fn update_item(menu_handle: isize, uuid: u16, f: impl FnOnce(&mut MenuItem)) {
+    /* Exit if menu is not built */
+    if menu_handle == 0 {
+        return;
+    }
+
+    with_menu_data(menu_handle, |data| {
+        if let Some(item) = data.items.iter_mut().find(|item| item.uuid == uuid) {
+            f(item);
+        }
+    });
+}
+
+/// Checks the radio item and unchecks the others in the same group. Returns the unchecked items.
+pub(crate) fn check_radio(menu_handle: isize, uuid: u16) -> Vec<MenuItem> {
+    with_menu_data(menu_handle, |data| {
+        let Some(name) = data.items.iter().find(|item| item.uuid == uuid).map(|item| item.name.clone()) else {
+            return Vec::new();
+        };
+
+        let mut unchecked_items = Vec::new();
+        for item in data.items.iter_mut().filter(|item| item.menu_item_type == MenuItemType::Radio && item.name == name) {
+            if item.uuid == uuid {
+                item.check_state = CheckState::Checked;
+            } else if item.check_state.is_checked() {
+                item.check_state = CheckState::Unchecked;
+                unchecked_items.push(item.clone());
+            }
+        }
+        unchecked_items
+    })
+}
+
+/// Toggles the MenuItem if needed and sends its events. Returns the updated MenuItem.
+fn activate_item(main_menu_handle: isize, item: MenuItem, source: ActivationSource) -> MenuItem {
+    let mut events = Vec::new();
+    let item = match item.menu_item_type {
+        MenuItemType::Checkbox => {
+            /* Clicking a mixed MenuItem checks it */
+            let check_state = CheckState::from(!item.check_state.is_checked());
+            update_item(item.menu_handle, item.uuid, |item| item.check_state = check_state);
+            let item = MenuItem {
+                check_state,
+                ..item
+            };
+            events.push(MenuEvent::Toggled {
+                item: item.clone(),
+                check_state,
+            });
+            item
+        }
+        MenuItemType::Radio => {
+            for unchecked_item in check_radio(item.menu_handle, item.uuid) {
+                events.push(MenuEvent::Toggled {
+                    item: unchecked_item,
+                    check_state: CheckState::Unchecked,
+                });
+            }
+            let item = MenuItem {
+                check_state: CheckState::Checked,
+                ..item
+            };
+            events.push(MenuEvent::Toggled {
+                item: item.clone(),
+                check_state: CheckState::Checked,
+            });
+            item
+        }
+        _ => item,
+    };
+
+    events.push(MenuEvent::Selected {
+        item: item.clone(),
+        source,
+    });
+
+    for event in events {
+        MenuEvent::send(main_menu_handle, event);
+    }
+
+    item
+}
+
+/* Reachable means the MenuItem can be reached by user, that is, no disabled or hidden submenu is in between */
+fn find_by_id(menu_handle: isize, id: &str, reachable_only: bool) -> Option<MenuItem> {
+    let items = with_menu_data(menu_handle, |data| data.items.clone());
+    for item in items {
+        if reachable_only && !item.visible {
+            continue;
+        }
+
+        if item.id == id {
+            return Some(item);
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu && !(reachable_only && item.disabled) {
+            if let Some(submenu) = item.submenu.as_ref() {
+                if let Some(item) = find_by_id(submenu.menu_handle, id, reachable_only) {
+                    return Some(item);
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Collects the accelerators of visible and enabled MenuItems including submenus.
+#[cfg(feature = "accelerator")]
+fn collect_chords(menu_handle: isize, chords: &mut Vec<(AcceleratorChord, MenuItem)>) {
+    let items = with_menu_data(menu_handle, |data| data.items.clone());
+    for item in items {
+        if !item.visible || item.disabled {
+            continue;
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            if let Some(submenu) = item.submenu.as_ref() {
+                collect_chords(submenu.menu_handle, chords);
+            }
+        } else if let Ok(chord) = item.accelerator.parse::<AcceleratorChord>() {
+            chords.push((chord, item));
+        }
+    }
+}
+
+fn collect_menu_handles(menu_handle: isize) -> Vec<isize> {
+    let mut menu_handles = vec![menu_handle];
+    let items = with_menu_data(menu_handle, |data| data.items.clone());
+    for submenu in items.iter().filter_map(|item| item.submenu.as_ref()) {
+        menu_handles.extend(collect_menu_handles(submenu.menu_handle));
+    }
+    menu_handles
+}
+
+fn remove_menu_data(menu_handle: isize) {
+    for menu_handle in collect_menu_handles(menu_handle) {
+        MENUS.lock().unwrap().remove(&menu_handle);
+    }
+}
@@ -0,0 +1,336 @@
+use super::*;
+use crate::{mnemonic::MNEMONIC_MARKER, MenuEventReceiver};
+
+fn build_menu() -> Menu {
+    let mut builder = MenuBuilder::new(0);
+    builder.text("open", "Open", false);
+    builder.text("locked", "Locked", true);
+    builder.check("wrap", "Wrap", false, false);
+    builder.radio("small", "Small", "size", true, false);
+    builder.radio("large", "Large", "size", false, false);
+    let mut submenu = builder.submenu("more", "More", false);
+    submenu.text("nested", "Nested", false);
+    submenu.build().unwrap();
+    builder.build().unwrap()
+}
+
+fn drain(receiver: &MenuEventReceiver) -> Vec<MenuEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = receiver.try_recv() {
+        events.push(event);
+    }
+    events
+}
+
+#[test]
+fn popup_at_opens_menu() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    assert!(!menu.is_open());
+
+    menu.popup_at(0, 0);
+
+    assert!(menu.is_open());
+    assert!(matches!(drain(&receiver)[..], [MenuEvent::Opened]));
+}
+
+#[test]
+fn popup_at_replaces_shown_popup() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    menu.popup_at(0, 0);
+
+    menu.popup_at(10, 10);
+
+    assert!(menu.is_open());
+    assert!(matches!(
+        drain(&receiver)[..],
+        [
+            MenuEvent::Opened,
+            MenuEvent::Closed {
+                reason: CloseReason::Dismissed(DismissReason::Replaced)
+            },
+            MenuEvent::Opened
+        ]
+    ));
+}
+
+#[test]
+fn activate_by_id_selects_item() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    menu.popup_at(0, 0);
+
+    assert!(menu.activate_by_id("open"));
+
+    assert!(!menu.is_open());
+    let events = drain(&receiver);
+    assert!(matches!(&events[..], [MenuEvent::Opened, MenuEvent::Selected { item, source: ActivationSource::Program }, MenuEvent::Closed { reason: CloseReason::Selected }] if item.id == "open"));
+}
+
+#[test]
+fn activate_by_id_selects_nested_item() {
+    let menu = build_menu();
+    menu.popup_at(0, 0);
+
+    assert!(menu.activate_by_id("nested"));
+    assert!(!menu.is_open());
+}
+
+#[test]
+fn activate_by_id_requires_shown_popup() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+
+    assert!(!menu.activate_by_id("open"));
+    assert!(drain(&receiver).is_empty());
+}
+
+#[test]
+fn activate_by_id_rejects_unselectable_items() {
+    let menu = build_menu();
+    menu.popup_at(0, 0);
+
+    assert!(!menu.activate_by_id("locked"));
+    assert!(!menu.activate_by_id("more"));
+    assert!(!menu.activate_by_id("unknown"));
+    assert!(menu.is_open());
+}
+
+#[test]
+fn activate_by_id_skips_items_in_disabled_submenu() {
+    let menu = build_menu();
+    let mut more = menu.get_menu_item_by_id("more").unwrap();
+    more.set_disabled(true);
+    menu.popup_at(0, 0);
+
+    assert!(!menu.activate_by_id("nested"));
+    assert!(menu.is_open());
+}
+
+#[test]
+fn activate_by_id_toggles_checkbox() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    menu.popup_at(0, 0);
+
+    assert!(menu.activate_by_id("wrap"));
+
+    assert_eq!(menu.get_menu_item_by_id("wrap").unwrap().check_state, CheckState::Checked);
+    let events = drain(&receiver);
+    assert!(matches!(&events[1], MenuEvent::Toggled { item, check_state: CheckState::Checked } if item.id == "wrap"));
+}
+
+#[test]
+fn activate_by_id_checks_mixed_checkbox() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    let mut wrap = menu.get_menu_item_by_id("wrap").unwrap();
+    wrap.set_check_state(CheckState::Mixed);
+    assert_eq!(menu.get_menu_item_by_id("wrap").unwrap().check_state, CheckState::Mixed);
+    menu.popup_at(0, 0);
+
+    assert!(menu.activate_by_id("wrap"));
+
+    assert_eq!(menu.get_menu_item_by_id("wrap").unwrap().check_state, CheckState::Checked);
+    let events = drain(&receiver);
+    assert!(matches!(&events[1], MenuEvent::Toggled { item, check_state: CheckState::Checked } if item.id == "wrap"));
+}
+
+#[test]
+fn activate_by_id_unchecks_other_radio() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    menu.popup_at(0, 0);
+
+    assert!(menu.activate_by_id("large"));
+
+    assert_eq!(menu.get_menu_item_by_id("small").unwrap().check_state, CheckState::Unchecked);
+    assert_eq!(menu.get_menu_item_by_id("large").unwrap().check_state, CheckState::Checked);
+    let events = drain(&receiver);
+    assert!(matches!(&events[1], MenuEvent::Toggled { item, check_state: CheckState::Unchecked } if item.id == "small"));
+    assert!(matches!(&events[2], MenuEvent::Toggled { item, check_state: CheckState::Checked } if item.id == "large"));
+}
+
+#[test]
+fn dismiss_closes_popup() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+    menu.popup_at(0, 0);
+
+    menu.dismiss();
+
+    assert!(!menu.is_open());
+    assert!(matches!(
+        drain(&receiver)[..],
+        [
+            MenuEvent::Opened,
+            MenuEvent::Closed {
+                reason: CloseReason::Dismissed(DismissReason::Escape)
+            }
+        ]
+    ));
+}
+
+#[test]
+fn dismiss_without_popup_sends_nothing() {
+    let menu = build_menu();
+    let receiver = menu.receiver();
+
+    menu.dismiss();
+
+    assert!(drain(&receiver).is_empty());
+}
+
+#[test]
+fn popup_at_async_resolves_with_selection() {
+    let menu = build_menu();
+    let popup = menu.clone();
+    let task = std::thread::spawn(move || smol::block_on(popup.popup_at_async(0, 0)));
+
+    while !menu.is_open() {
+        std::thread::yield_now();
+    }
+    assert!(menu.activate_by_id("open"));
+
+    assert!(matches!(task.join().unwrap(), PopupResult::Selected(item) if item.id == "open"));
+}
+
+#[test]
+fn popup_at_async_resolves_with_dismissal() {
+    let menu = build_menu();
+    let popup = menu.clone();
+    let task = std::thread::spawn(move || smol::block_on(popup.popup_at_async(0, 0)));
+
+    while !menu.is_open() {
+        std::thread::yield_now();
+    }
+    menu.dismiss();
+
+    assert!(matches!(task.join().unwrap(), PopupResult::Dismissed(DismissReason::Escape)));
+}
+
+#[test]
+fn subscribe_receives_events_until_unsubscribed() {
+    let menu = build_menu();
+    let (sender, receiver) = async_channel::unbounded();
+    let subscription = menu.subscribe(move |event| {
+        let _ = sender.send_blocking(event);
+    });
+
+    menu.popup_at(0, 0);
+    menu.dismiss();
+    assert_eq!(drain(&receiver).len(), 2);
+
+    subscription.unsubscribe();
+    menu.popup_at(0, 0);
+    assert!(drain(&receiver).is_empty());
+}
+
+#[test]
+fn receiver_ignores_other_menus() {
+    let menu = build_menu();
+    let other = build_menu();
+    let receiver = menu.receiver();
+
+    other.popup_at(0, 0);
+    other.dismiss();
+
+    assert!(drain(&receiver).is_empty());
+}
+
+#[test]
+fn accelerator_conflicts_are_kept_as_warnings() {
+    let mut builder = MenuBuilder::new(0);
+    builder.text_with_accelerator("save", "Save", false, "Ctrl+S");
+    builder.text_with_accelerator("save_as", "Save As", false, "Ctrl+S");
+    let mut menu = builder.build().unwrap();
+
+    let conflicts = menu.take_accelerator_conflicts();
+    assert_eq!(conflicts.len(), 1);
+    assert_eq!(conflicts[0].id, "save_as");
+    assert_eq!(conflicts[0].other_id, "save");
+    assert!(menu.take_accelerator_conflicts().is_empty());
+
+    menu.append(MenuItem::new_text_item("print", "Print", Some("Ctrl+S"), false, None)).unwrap();
+    assert_eq!(menu.take_accelerator_conflicts().len(), 1);
+}
+
+#[test]
+fn accelerator_conflicts_fail_with_error_policy() {
+    let config = Config {
+        accelerator_conflict: AcceleratorConflictPolicy::Error,
+        ..Default::default()
+    };
+    let mut builder = MenuBuilder::new_from_config(0, config);
+    builder.text_with_accelerator("save", "Save", false, "Ctrl+S");
+    builder.text_with_accelerator("save_as", "Save As", false, "Ctrl+S");
+
+    assert!(matches!(builder.build(), Err(Error::AcceleratorConflict(conflict)) if conflict.id == "save_as"));
+}
+
+fn build_mnemonic_menu(mnemonic: bool) -> Menu {
+    let config = Config {
+        mnemonic,
+        ..Default::default()
+    };
+    let mut builder = MenuBuilder::new_from_config(0, config);
+    builder.text("file", &format!("my{}file.txt", MNEMONIC_MARKER), false);
+    builder.text("escaped", &format!("{0}{0}Escaped", MNEMONIC_MARKER), false);
+    builder.build().unwrap()
+}
+
+#[test]
+fn press_mnemonic_is_disabled_by_default() {
+    let menu = build_mnemonic_menu(false);
+    menu.popup_at(0, 0);
+
+    assert!(!menu.press_mnemonic('f'));
+    assert_eq!(menu.search(&format!("my{}", MNEMONIC_MARKER)).len(), 1);
+    assert!(menu.is_open());
+}
+
+#[test]
+fn press_mnemonic_selects_item_when_enabled() {
+    let menu = build_mnemonic_menu(true);
+    let receiver = menu.receiver();
+    menu.popup_at(0, 0);
+
+    assert_eq!(menu.search(&format!("my{}", MNEMONIC_MARKER)).len(), 0);
+    assert!(!menu.press_mnemonic('e'));
+    assert!(menu.press_mnemonic('F'));
+
+    let events = drain(&receiver);
+    assert!(matches!(&events[1], MenuEvent::Selected { item, source: ActivationSource::Menu } if item.id == "file"));
+}
+
+#[test]
+fn from_model_reproduces_menu() {
+    let config = Config {
+        theme: Theme::Dark,
+        ..Default::default()
+    };
+    let mut builder = MenuBuilder::new_from_config(0, config);
+    builder.header_with_id("recent", "Recent");
+    builder.append(MenuItem::builder(MenuItemType::Text).id("open").label("Open").accelerator("Ctrl+K Ctrl+O").keywords(&["load"]).tooltip("Open a file").description("From disk").build());
+    builder.append(MenuItem::builder(MenuItemType::Checkbox).id("bold").label("Bold").check_state(CheckState::Mixed).build());
+    builder.radio("small", "Small", "size", false, false);
+    builder.radio("large", "Large", "size", true, true);
+    builder.separator();
+    let mut submenu = builder.submenu("more", "More", false);
+    submenu.append(MenuItem::builder(MenuItemType::Text).id("hidden").label("Hidden").visible(false).build());
+    submenu.build().unwrap();
+    let menu = builder.build().unwrap();
+
+    let model = menu.to_model();
+    let rebuilt = Menu::from_model(0, &model).unwrap();
+    let rebuilt_model = rebuilt.to_model();
+
+    assert_eq!(format!("{:?}", rebuilt_model.items), format!("{:?}", model.items));
+    assert_eq!(rebuilt.config().theme, Theme::Dark);
+    assert_eq!(rebuilt.get_menu_item_by_id("bold").unwrap().check_state, CheckState::Mixed);
+    assert_eq!(rebuilt.get_menu_item_by_id("large").unwrap().name, "size");
+    assert!(rebuilt.get_menu_item_by_id("large").unwrap().disabled);
+    assert!(!rebuilt.get_menu_item_by_id("hidden").unwrap().visible);
+    assert_eq!(rebuilt.get_menu_item_by_id("open").unwrap().accelerator, "Ctrl+K Ctrl+O");
+}
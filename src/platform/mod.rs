@@ -1,6 +1,9 @@
-#[cfg(target_os = "linux")]
+#[cfg(all(target_os = "linux", not(feature = "mock")))]
 #[path = "gtk/mod.rs"]
 pub(crate) mod platform_impl;
-#[cfg(target_os = "windows")]
+#[cfg(all(target_os = "windows", not(feature = "mock")))]
 #[path = "windows/mod.rs"]
 pub(crate) mod platform_impl;
+#[cfg(feature = "mock")]
+#[path = "mock/mod.rs"]
+pub(crate) mod platform_impl;
@@ -3,26 +3,25 @@ use super::{get_menu_data, MenuData};
 #[cfg(feature = "accelerator")]
 use crate::platform::platform_impl::vtoi;
 #[cfg(feature = "accelerator")]
+use crate::Accelerator;
+#[cfg(feature = "accelerator")]
 use std::collections::HashMap;
 #[cfg(feature = "accelerator")]
 use windows::Win32::{
     Foundation::HWND,
     UI::{
         Input::KeyboardAndMouse::{
-            VIRTUAL_KEY, VK_0, VK_1, VK_2, VK_3, VK_4, VK_5, VK_6, VK_7, VK_8, VK_9, VK_A, VK_APPS, VK_B, VK_BACK, VK_BROWSER_HOME, VK_C, VK_CAPITAL, VK_CONVERT, VK_D, VK_DELETE, VK_DOWN, VK_E,
-            VK_END, VK_ESCAPE, VK_F, VK_F1, VK_F10, VK_F11, VK_F12, VK_F13, VK_F14, VK_F15, VK_F16, VK_F17, VK_F18, VK_F19, VK_F2, VK_F20, VK_F21, VK_F22, VK_F23, VK_F24, VK_F3, VK_F4, VK_F5, VK_F6,
-            VK_F7, VK_F8, VK_F9, VK_G, VK_H, VK_HELP, VK_HOME, VK_I, VK_INSERT, VK_J, VK_K, VK_KANA, VK_L, VK_LEFT, VK_M, VK_MEDIA_NEXT_TRACK, VK_MEDIA_PLAY_PAUSE, VK_MEDIA_PREV_TRACK, VK_MEDIA_STOP,
-            VK_N, VK_NEXT, VK_NONCONVERT, VK_NUMLOCK, VK_O, VK_OEM_1, VK_OEM_2, VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6, VK_OEM_7, VK_OEM_COMMA, VK_OEM_MINUS, VK_OEM_PERIOD, VK_OEM_PLUS, VK_P,
-            VK_PAUSE, VK_PRIOR, VK_Q, VK_R, VK_RETURN, VK_RIGHT, VK_S, VK_SCROLL, VK_SNAPSHOT, VK_SPACE, VK_T, VK_TAB, VK_U, VK_UP, VK_V, VK_VOLUME_DOWN, VK_VOLUME_MUTE, VK_VOLUME_UP, VK_W, VK_X,
-            VK_Y, VK_Z,
+            GetKeyState, VIRTUAL_KEY, VK_0, VK_1, VK_2, VK_3, VK_4, VK_5, VK_6, VK_7, VK_8, VK_9, VK_A, VK_APPS, VK_B, VK_BACK, VK_BROWSER_HOME, VK_C, VK_CAPITAL, VK_CONTROL, VK_CONVERT, VK_D,
+            VK_DELETE, VK_DOWN, VK_E, VK_END, VK_ESCAPE, VK_F, VK_F1, VK_F10, VK_F11, VK_F12, VK_F13, VK_F14, VK_F15, VK_F16, VK_F17, VK_F18, VK_F19, VK_F2, VK_F20, VK_F21, VK_F22, VK_F23, VK_F24,
+            VK_F3, VK_F4, VK_F5, VK_F6, VK_F7, VK_F8, VK_F9, VK_G, VK_H, VK_HELP, VK_HOME, VK_I, VK_INSERT, VK_J, VK_K, VK_KANA, VK_L, VK_LEFT, VK_LWIN, VK_M, VK_MEDIA_NEXT_TRACK,
+            VK_MEDIA_PLAY_PAUSE, VK_MEDIA_PREV_TRACK, VK_MEDIA_STOP, VK_MENU, VK_N, VK_NEXT, VK_NONCONVERT, VK_NUMLOCK, VK_O, VK_OEM_1, VK_OEM_2, VK_OEM_3, VK_OEM_4, VK_OEM_5, VK_OEM_6, VK_OEM_7,
+            VK_OEM_COMMA, VK_OEM_MINUS, VK_OEM_PERIOD, VK_OEM_PLUS, VK_P, VK_PAUSE, VK_PRIOR, VK_Q, VK_R, VK_RETURN, VK_RIGHT, VK_RWIN, VK_S, VK_SCROLL, VK_SHIFT, VK_SNAPSHOT, VK_SPACE, VK_T, VK_TAB,
+            VK_U, VK_UP, VK_V, VK_VOLUME_DOWN, VK_VOLUME_MUTE, VK_VOLUME_UP, VK_W, VK_X, VK_Y, VK_Z,
         },
         WindowsAndMessaging::{CreateAcceleratorTableW, DestroyAcceleratorTable, TranslateAcceleratorW, ACCEL, FALT, FCONTROL, FSHIFT, FVIRTKEY, HACCEL, MSG},
     },
 };
 
-#[cfg(feature = "accelerator")]
-const MODIFIERS: [&str; 3] = ["CTRL", "ALT", "SHIFT"];
-
 #[cfg(feature = "accelerator")]
 pub(crate) fn translate_accel(hwnd: HWND, msg: MSG) {
     let data = get_menu_data(vtoi!(hwnd.0));
@@ -44,17 +43,18 @@ pub(crate) fn create_haccel(accelerators: &HashMap<u16, String>) -> Option<HACCE
     let mut accels = Vec::new();
 
     for (cmd, accel_key) in accelerators {
-        let upper_key = accel_key.to_uppercase();
-        let upper_keys: Vec<&str> = upper_key.split('+').collect();
+        /* Invalid accelerator is reported by MenuBuilder::build */
+        let Ok(accelerator) = accel_key.parse::<Accelerator>() else {
+            continue;
+        };
 
-        if MODIFIERS.contains(&upper_keys[upper_keys.len() - 1]) {
+        let modifiers = accelerator.modifiers();
+        /* Accelerator table does not support the Windows logo key */
+        if modifiers.super_key {
             continue;
         }
 
-        let keys: Vec<&str> = accel_key.split('+').collect();
-        let key = keys[keys.len() - 1];
-
-        let virtual_key = get_virtual_key(key);
+        let virtual_key = get_virtual_key(accelerator.key());
 
         if virtual_key == VIRTUAL_KEY(0) {
             continue;
@@ -62,15 +62,15 @@ pub(crate) fn create_haccel(accelerators: &HashMap<u16, String>) -> Option<HACCE
 
         let mut virt = FVIRTKEY;
 
-        if upper_keys.contains(&"CTRL") {
+        if modifiers.ctrl {
             virt |= FCONTROL;
         }
 
-        if upper_keys.contains(&"ALT") {
+        if modifiers.alt {
             virt |= FALT;
         }
 
-        if upper_keys.contains(&"SHIFT") {
+        if modifiers.shift {
             virt |= FSHIFT;
         }
 
@@ -92,6 +92,19 @@ pub(crate) fn create_haccel(accelerators: &HashMap<u16, String>) -> Option<HACCE
     }
 }
 
+/// Returns true if the accelerator matches the pressed key and the current modifier key state.
+#[cfg(feature = "accelerator")]
+pub(crate) fn is_accelerator_pressed(accelerator: &Accelerator, virtual_key: VIRTUAL_KEY) -> bool {
+    if get_virtual_key(accelerator.key()) != virtual_key {
+        return false;
+    }
+
+    let is_down = |key: VIRTUAL_KEY| unsafe { GetKeyState(key.0 as i32) } < 0;
+    let modifiers = accelerator.modifiers();
+
+    modifiers.ctrl == is_down(VK_CONTROL) && modifiers.alt == is_down(VK_MENU) && modifiers.shift == is_down(VK_SHIFT) && modifiers.super_key == (is_down(VK_LWIN) || is_down(VK_RWIN))
+}
+
 #[cfg(feature = "accelerator")]
 fn get_virtual_key(key_string: &str) -> VIRTUAL_KEY {
     match key_string {
@@ -155,7 +168,7 @@ fn get_virtual_key(key_string: &str) -> VIRTUAL_KEY {
         "F22" => VK_F22,
         "F23" => VK_F23,
         "F24" => VK_F24,
-        "Plus" => VK_OEM_PLUS,
+        "Plus" | "Equal" => VK_OEM_PLUS,
         "Space" => VK_SPACE,
         "Tab" => VK_TAB,
         "CapsLock" => VK_CAPITAL,
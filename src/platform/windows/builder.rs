@@ -2,35 +2,35 @@
 use super::{accelerator::create_haccel, get_menu_data};
 use super::{
     calculate, hwnd,
-    image::{create_check_svg, create_menu_image, create_render_target, create_submenu_svg, get_icon_space, MenuImageType},
+    image::{create_check_svg, create_menu_image, create_mixed_svg, create_render_target, create_submenu_svg, get_icon_space, MenuImageType},
     is_win11,
     menu_item::MenuItem,
     util::set_window_border_color,
     IconSpace, Menu, PopupInfo, Size,
 };
+#[cfg(feature = "accelerator")]
+use crate::accelerator::ChordState;
 use crate::{
-    config::{Config, Corner, IconSettings, Theme},
-    MenuIcon, MenuItemType, MenuType,
+    config::{Config, Corner, IconSettings, SearchSettings, Theme},
+    validation::{validate_accelerator_conflicts, validate_config, validate_items},
+    AcceleratorConflict, Error, MenuIcon, MenuItemType, MenuType, PopupSender,
 };
 #[cfg(feature = "accelerator")]
 use std::rc::Rc;
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     mem::size_of,
     sync::atomic::{AtomicU32, Ordering},
 };
 #[cfg(feature = "accelerator")]
 use windows::Win32::UI::WindowsAndMessaging::HACCEL;
-use windows::{
-    core::Error,
-    Win32::{
-        Foundation::HWND,
-        Graphics::{
-            Direct2D::ID2D1DCRenderTarget,
-            Dwm::{DwmSetWindowAttribute, DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_ROUND, DWM_WINDOW_CORNER_PREFERENCE},
-        },
-        UI::WindowsAndMessaging::{SetWindowLongPtrW, GWL_USERDATA},
+use windows::Win32::{
+    Foundation::HWND,
+    Graphics::{
+        Direct2D::ID2D1DCRenderTarget,
+        Dwm::{DwmSetWindowAttribute, DWMWA_WINDOW_CORNER_PREFERENCE, DWMWCP_ROUND, DWM_WINDOW_CORNER_PREFERENCE},
     },
+    UI::WindowsAndMessaging::{SetWindowLongPtrW, GWL_USERDATA},
 };
 
 static COUNTER: AtomicU32 = AtomicU32::new(400);
@@ -38,6 +38,7 @@ static COUNTER: AtomicU32 = AtomicU32::new(400);
 #[derive(Debug)]
 pub(crate) struct MenuData {
     pub(crate) popup_info: Option<PopupInfo>,
+    pub(crate) popup_sender: Option<PopupSender>,
     pub(crate) menu_type: MenuType,
     pub(crate) items: Vec<MenuItem>,
     pub(crate) win_subclass_id: u32,
@@ -50,12 +51,21 @@ pub(crate) struct MenuData {
     pub(crate) parent: isize,
     pub(crate) dc_render_target: ID2D1DCRenderTarget,
     pub(crate) check_icon: MenuImageType,
+    pub(crate) mixed_icon: MenuImageType,
     pub(crate) submenu_icon: MenuImageType,
     pub(crate) icon_map: HashMap<u16, MenuImageType>,
+    /* Tooltip control created when a tooltip is shown first */
+    pub(crate) tooltip_window_handle: isize,
+    /* Conflicts found under AcceleratorConflictPolicy::Warn, kept in the main menu */
+    pub(crate) accelerator_conflicts: Vec<AcceleratorConflict>,
     #[cfg(feature = "accelerator")]
     pub(crate) haccel: Option<Rc<HACCEL>>,
     #[cfg(feature = "accelerator")]
     pub(crate) accelerators: HashMap<u16, String>,
+    #[cfg(feature = "accelerator")]
+    pub(crate) window_shortcuts: bool,
+    #[cfg(feature = "accelerator")]
+    pub(crate) chord_state: ChordState,
 }
 
 /// Builder to create Menu.
@@ -163,8 +173,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn text_with_accelerator(&mut self, id: &str, label: &str, disabled: bool, accelerator: &str) -> &Self {
-        let mut item = MenuItem::new_text_item(id, label, Some(accelerator), disabled, None);
+    pub fn text_with_accelerator(&mut self, id: &str, label: &str, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_text_item(id, label, Some(accelerator.as_str()), disabled, None);
         item.menu_window_handle = self.menu.window_handle;
         self.items.push(item);
         self
@@ -177,8 +188,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn text_with_accel_icon(&mut self, id: &str, label: &str, disabled: bool, accelerator: &str, icon: MenuIcon) -> &Self {
-        let mut item = MenuItem::new_text_item(id, label, Some(accelerator), disabled, Some(icon));
+    pub fn text_with_accel_icon(&mut self, id: &str, label: &str, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_text_item(id, label, Some(accelerator.as_str()), disabled, Some(icon));
         item.menu_window_handle = self.menu.window_handle;
         self.items.push(item);
         self
@@ -192,8 +204,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn check_with_accelerator(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: &str) -> &Self {
-        let mut item = MenuItem::new_check_item(id, label, Some(accelerator), checked, disabled, None);
+    pub fn check_with_accelerator(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_check_item(id, label, Some(accelerator.as_str()), checked, disabled, None);
         item.menu_window_handle = self.menu.window_handle;
         self.items.push(item);
         self
@@ -206,8 +219,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn check_with_accel_icon(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: &str, icon: MenuIcon) -> &Self {
-        let mut item = MenuItem::new_check_item(id, label, Some(accelerator), checked, disabled, Some(icon));
+    pub fn check_with_accel_icon(&mut self, id: &str, label: &str, checked: bool, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_check_item(id, label, Some(accelerator.as_str()), checked, disabled, Some(icon));
         item.menu_window_handle = self.menu.window_handle;
         self.items.push(item);
         self
@@ -221,8 +235,9 @@ impl MenuBuilder {
         self
     }
 
-    pub fn radio_with_accelerator(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: &str) -> &Self {
-        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator), checked, disabled, None);
+    pub fn radio_with_accelerator(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: impl Into<String>) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator.as_str()), checked, disabled, None);
         item.menu_window_handle = self.menu.window_handle;
         self.items.push(item);
         self
@@ -236,8 +251,9 @@ impl MenuBuilder {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn radio_with_accel_icon(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: &str, icon: MenuIcon) -> &Self {
-        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator), checked, disabled, Some(icon));
+    pub fn radio_with_accel_icon(&mut self, id: &str, label: &str, name: &str, checked: bool, disabled: bool, accelerator: impl Into<String>, icon: MenuIcon) -> &Self {
+        let accelerator: String = accelerator.into();
+        let mut item = MenuItem::new_radio_item(id, label, name, Some(accelerator.as_str()), checked, disabled, Some(icon));
         item.menu_window_handle = self.menu.window_handle;
         self.items.push(item);
         self
@@ -258,6 +274,21 @@ impl MenuBuilder {
         self
     }
 
+    /// Adds a section heading to Menu. It cannot be selected.
+    pub fn header(&mut self, label: &str) -> &Self {
+        let mut item = MenuItem::new_header(label);
+        item.menu_window_handle = self.menu.window_handle;
+        self.items.push(item);
+        self
+    }
+
+    pub fn header_with_id(&mut self, id: &str, label: &str) -> &Self {
+        let mut item = MenuItem::new_header_with_id(id, label);
+        item.menu_window_handle = self.menu.window_handle;
+        self.items.push(item);
+        self
+    }
+
     /// Adds a submenu MenuItem to Menu.
     pub fn submenu(&mut self, id: &str, label: &str, disabled: bool) -> Self {
         let mut item = MenuItem::new(self.menu.window_handle, id, label, "", "", false, disabled, MenuItemType::Submenu, None, None);
@@ -312,11 +343,25 @@ impl MenuBuilder {
         self
     }
 
+    /// Enables the search field of the main Menu. The field is shown only on Linux, while Menu.search() works on Windows as well.
+    pub fn search(&mut self, settings: SearchSettings) -> &Self {
+        self.config.search = Some(settings);
+        self
+    }
+
     /// Build Menu to make it ready to become visible.
     /// Must call this function before showing Menu, otherwise nothing shows up.
     pub fn build(mut self) -> Result<Menu, Error> {
         let is_main_menu = self.menu_type == MenuType::Main;
 
+        /* Submenus are validated as part of the main menu */
+        let mut warnings = Vec::new();
+        if is_main_menu {
+            validate_config(&self.config)?;
+            validate_items(&self.items, &mut HashSet::new())?;
+            validate_accelerator_conflicts(&self.items, &mut HashMap::new(), self.config.accelerator_conflict, &mut warnings)?;
+        }
+
         #[cfg(feature = "accelerator")]
         let mut accelerators = HashMap::new();
         #[cfg(feature = "accelerator")]
@@ -340,6 +385,12 @@ impl MenuBuilder {
             MenuImageType::Svg(create_check_svg(&dc_render_target, &self.config)?)
         };
 
+        let mixed_icon = if let Some(mixed_icon) = &self.config.icon.as_ref().unwrap().mixed {
+            create_menu_image(&dc_render_target, mixed_icon)?
+        } else {
+            MenuImageType::Svg(create_mixed_svg(&dc_render_target, &self.config)?)
+        };
+
         let submenu_icon = if let Some(submenu_icon) = &self.config.icon.as_ref().unwrap().arrow {
             create_menu_image(&dc_render_target, submenu_icon)?
         } else {
@@ -377,10 +428,16 @@ impl MenuBuilder {
             haccel,
             #[cfg(feature = "accelerator")]
             accelerators,
+            #[cfg(feature = "accelerator")]
+            window_shortcuts: false,
+            #[cfg(feature = "accelerator")]
+            chord_state: ChordState::default(),
             size: menu_size,
             icon_space,
             selected_index: -1,
             visible_submenu_index: -1,
+            tooltip_window_handle: 0,
+            accelerator_conflicts: warnings,
             current_theme: self.theme,
             config: self.config,
             parent: if is_main_menu {
@@ -390,8 +447,10 @@ impl MenuBuilder {
             },
             dc_render_target,
             check_icon,
+            mixed_icon,
             submenu_icon,
             popup_info: None,
+            popup_sender: None,
             icon_map,
         };
 
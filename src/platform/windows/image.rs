@@ -12,7 +12,8 @@ use windows::{
                 Common::{D2D1_ALPHA_MODE_IGNORE, D2D1_ALPHA_MODE_PREMULTIPLIED, D2D1_COLOR_F, D2D1_PIXEL_FORMAT, D2D_RECT_F, D2D_SIZE_F},
                 D2D1CreateFactory, ID2D1Bitmap1, ID2D1DCRenderTarget, ID2D1DeviceContext5, ID2D1Factory1, ID2D1SvgDocument, ID2D1SvgElement, D2D1_BITMAP_PROPERTIES1,
                 D2D1_FACTORY_TYPE_SINGLE_THREADED, D2D1_FEATURE_LEVEL_DEFAULT, D2D1_RENDER_TARGET_PROPERTIES, D2D1_RENDER_TARGET_TYPE_DEFAULT, D2D1_RENDER_TARGET_USAGE_NONE,
-                D2D1_SVG_ATTRIBUTE_STRING_TYPE_SVG, D2D1_SVG_PATH_COMMAND_ARC_RELATIVE, D2D1_SVG_PATH_COMMAND_LINE_ABSOLUTE, D2D1_SVG_PATH_COMMAND_LINE_RELATIVE, D2D1_SVG_PATH_COMMAND_MOVE_ABSOLUTE,
+                D2D1_SVG_ATTRIBUTE_STRING_TYPE_SVG, D2D1_SVG_PATH_COMMAND_ARC_RELATIVE, D2D1_SVG_PATH_COMMAND_CLOSE_PATH, D2D1_SVG_PATH_COMMAND_HORIZONTAL_RELATIVE,
+                D2D1_SVG_PATH_COMMAND_LINE_ABSOLUTE, D2D1_SVG_PATH_COMMAND_LINE_RELATIVE, D2D1_SVG_PATH_COMMAND_MOVE_ABSOLUTE, D2D1_SVG_PATH_COMMAND_VERTICAL_RELATIVE,
             },
             DirectWrite::{
                 DWriteCreateFactory, IDWriteFactory, IDWriteTextFormat, DWRITE_FACTORY_TYPE_SHARED, DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STYLE_NORMAL, DWRITE_FONT_WEIGHT_BOLD,
@@ -153,6 +154,56 @@ pub(crate) fn create_check_svg(target: &ID2D1DCRenderTarget, config: &Config) ->
     })
 }
 
+/// Creates a horizontal bar in the same size as the checkmark.
+pub(crate) fn create_mixed_svg(target: &ID2D1DCRenderTarget, config: &Config) -> Result<SvgDocument, Error> {
+    let dc5 = get_device_context(target)?;
+
+    let document = unsafe {
+        dc5.CreateSvgDocument(
+            None,
+            D2D_SIZE_F {
+                width: 1.0,
+                height: 1.0,
+            },
+        )?
+    };
+
+    let font_size = font_point_to_pixel(config.font.dark_font_size.max(config.font.light_font_size));
+    let size = font_size.ceil();
+    unsafe {
+        document.SetViewportSize(D2D_SIZE_F {
+            width: size,
+            height: size,
+        })?
+    };
+
+    let root = unsafe { document.GetRoot() }?;
+    unsafe { root.SetAttributeValue3(w!("fill"), D2D1_SVG_ATTRIBUTE_STRING_TYPE_SVG, w!("currentColor"))? };
+    unsafe { root.SetAttributeValue3(w!("viewBox"), D2D1_SVG_ATTRIBUTE_STRING_TYPE_SVG, w!("-0.8 -0.8 16 16"))? };
+
+    let segmentdata = [3.5, 7.25, 9.0, 1.5, -9.0];
+
+    let commands = [
+        D2D1_SVG_PATH_COMMAND_MOVE_ABSOLUTE,
+        D2D1_SVG_PATH_COMMAND_HORIZONTAL_RELATIVE,
+        D2D1_SVG_PATH_COMMAND_VERTICAL_RELATIVE,
+        D2D1_SVG_PATH_COMMAND_HORIZONTAL_RELATIVE,
+        D2D1_SVG_PATH_COMMAND_CLOSE_PATH,
+    ];
+    let path = unsafe { document.CreatePathData(Some(&segmentdata), Some(&commands))? };
+
+    let root = unsafe { document.GetRoot()? };
+    let element = unsafe { root.CreateChild(w!("path"))? };
+    unsafe { element.SetAttributeValue(w!("d"), &path)? };
+
+    unsafe { root.AppendChild(&element)? };
+
+    Ok(SvgDocument {
+        document,
+        color_required: true,
+    })
+}
+
 pub(crate) fn create_submenu_svg(target: &ID2D1DCRenderTarget, config: &Config) -> Result<SvgDocument, Error> {
     let dc5 = get_device_context(target)?;
 
@@ -367,6 +418,36 @@ pub(crate) fn get_text_format(factory: &IDWriteFactory, theme: Theme, config: &C
         Theme::System => (0.0, FontWeight::Normal),
     };
 
+    create_text_format(factory, config, font_size, font_weight_value, alignment)
+}
+
+/// Text format of MenuItem description, which uses the font weight of the label in a smaller size.
+pub(crate) fn get_description_text_format(factory: &IDWriteFactory, theme: Theme, config: &Config) -> Result<IDWriteTextFormat, Error> {
+    let current_theme = get_current_theme(theme);
+
+    let (font_size, font_weight_value) = match current_theme {
+        Theme::Dark => (config.font.dark_description_font_size, config.font.dark_font_weight),
+        Theme::Light => (config.font.light_description_font_size, config.font.light_font_weight),
+        /* get_current_theme never returns System */
+        Theme::System => (0.0, FontWeight::Normal),
+    };
+
+    create_text_format(factory, config, font_size, font_weight_value, TextAlignment::Leading)
+}
+
+/// Text format of header, which uses the description size in bold.
+pub(crate) fn get_header_text_format(factory: &IDWriteFactory, theme: Theme, config: &Config) -> Result<IDWriteTextFormat, Error> {
+    let font_size = match get_current_theme(theme) {
+        Theme::Dark => config.font.dark_description_font_size,
+        Theme::Light => config.font.light_description_font_size,
+        /* get_current_theme never returns System */
+        Theme::System => 0.0,
+    };
+
+    create_text_format(factory, config, font_size, FontWeight::Bold, TextAlignment::Leading)
+}
+
+fn create_text_format(factory: &IDWriteFactory, config: &Config, font_size: f32, font_weight_value: FontWeight, alignment: TextAlignment) -> Result<IDWriteTextFormat, Error> {
     let font_weight = match font_weight_value {
         FontWeight::Thin => DWRITE_FONT_WEIGHT_THIN,
         FontWeight::Light => DWRITE_FONT_WEIGHT_LIGHT,
@@ -402,6 +483,24 @@ pub(crate) fn get_text_metrics(factory: &IDWriteFactory, theme: Theme, config: &
     Ok(textmetrics)
 }
 
+pub(crate) fn get_description_metrics(factory: &IDWriteFactory, theme: Theme, config: &Config, text: &mut Vec<u16>) -> Result<DWRITE_TEXT_METRICS, Error> {
+    let format = get_description_text_format(factory, theme, config)?;
+    get_format_metrics(factory, &format, text)
+}
+
+pub(crate) fn get_header_metrics(factory: &IDWriteFactory, theme: Theme, config: &Config, text: &mut Vec<u16>) -> Result<DWRITE_TEXT_METRICS, Error> {
+    let format = get_header_text_format(factory, theme, config)?;
+    get_format_metrics(factory, &format, text)
+}
+
+fn get_format_metrics(factory: &IDWriteFactory, format: &IDWriteTextFormat, text: &mut Vec<u16>) -> Result<DWRITE_TEXT_METRICS, Error> {
+    let layout = unsafe { factory.CreateTextLayout(text.as_mut(), format, 0.0, 0.0)? };
+    let mut textmetrics = DWRITE_TEXT_METRICS::default();
+    unsafe { layout.GetMetrics(&mut textmetrics)? };
+
+    Ok(textmetrics)
+}
+
 pub(crate) fn to_2d_rect(rect: &RECT) -> D2D_RECT_F {
     D2D_RECT_F {
         left: rect.left as f32,
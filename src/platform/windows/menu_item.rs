@@ -4,7 +4,7 @@ use super::{
     util::{get_menu_data_mut, toggle_radio},
     Menu,
 };
-use crate::{MenuIcon, MenuItemType};
+use crate::{validation::validate_icon, CheckState, Error, MenuIcon, MenuItemType};
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU16, Ordering};
 
@@ -19,10 +19,21 @@ pub struct MenuItem {
     pub name: String,
     pub menu_item_type: MenuItemType,
     pub submenu: Option<Menu>,
-    pub checked: bool,
+    /// Check state of a checkbox or radio. Mixed is only for checkbox.
+    #[serde(default)]
+    pub check_state: CheckState,
     pub disabled: bool,
     pub visible: bool,
     pub icon: Option<MenuIcon>,
+    /// Extra words matched by search besides the label.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Text shown on hover, also for disabled MenuItems.
+    #[serde(default)]
+    pub tooltip: Option<String>,
+    /// Second line shown under the label in smaller and dimmer text.
+    #[serde(default)]
+    pub description: Option<String>,
     pub uuid: u16,
     pub index: u32,
     pub(crate) menu_window_handle: isize,
@@ -61,11 +72,14 @@ impl MenuItem {
             top: 0,
             right: 0,
             bottom: 0,
-            checked,
+            check_state: checked.into(),
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
         }
     }
 
@@ -81,6 +95,43 @@ impl MenuItem {
         recalculate(data);
     }
 
+    /// Sets the words matched by search besides the label.
+    pub fn set_keywords(&mut self, keywords: &[&str]) {
+        self.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+
+        /* Exit if window is not created */
+        if self.menu_window_handle == 0 {
+            return;
+        }
+        let data = get_menu_data_mut(self.menu_window_handle);
+        data.items[self.index as usize].keywords.clone_from(&self.keywords);
+    }
+
+    /// Sets the second line shown under the label. None removes it.
+    pub fn set_description(&mut self, description: Option<&str>) {
+        self.description = description.map(str::to_string);
+
+        /* Exit if window is not created */
+        if self.menu_window_handle == 0 {
+            return;
+        }
+        let data = get_menu_data_mut(self.menu_window_handle);
+        data.items[self.index as usize].description.clone_from(&self.description);
+        recalculate(data);
+    }
+
+    /// Sets the text shown on hover. A disabled MenuItem shows it as well, for example to explain why it is disabled.
+    pub fn set_tooltip(&mut self, tooltip: Option<&str>) {
+        self.tooltip = tooltip.map(str::to_string);
+
+        /* Exit if window is not created */
+        if self.menu_window_handle == 0 {
+            return;
+        }
+        let data = get_menu_data_mut(self.menu_window_handle);
+        data.items[self.index as usize].tooltip.clone_from(&self.tooltip);
+    }
+
     pub fn set_disabled(&mut self, disabled: bool) {
         self.disabled = disabled;
 
@@ -105,32 +156,41 @@ impl MenuItem {
         recalculate(data);
     }
 
-    pub fn set_icon(&mut self, icon: Option<MenuIcon>) {
-        if self.menu_item_type == MenuItemType::Separator {
-            return;
+    pub fn set_icon(&mut self, icon: Option<MenuIcon>) -> Result<(), Error> {
+        if !self.menu_item_type.is_selectable() {
+            return Ok(());
         }
 
-        self.icon = icon;
+        if let Some(icon) = &icon {
+            validate_icon(icon)?;
+        }
 
         /* Exit if window is not created */
         if self.menu_window_handle == 0 {
-            return;
+            self.icon = icon;
+            return Ok(());
         }
 
         let data = get_menu_data_mut(self.menu_window_handle);
 
+        /* Create bitmap before removing the current one so that the icon is kept on error */
+        let bitmap = match &icon {
+            Some(icon) => Some(create_menu_image(&data.dc_render_target, icon)?),
+            None => None,
+        };
+
+        self.icon = icon;
         let _ = data.icon_map.remove(&self.uuid);
 
         /* If icon is None, remove only */
-        if let Some(icon) = &self.icon {
-            if let Ok(bitmap) = create_menu_image(&data.dc_render_target, icon) {
-                data.icon_map.insert(self.uuid, bitmap);
-            }
+        if let Some(bitmap) = bitmap {
+            data.icon_map.insert(self.uuid, bitmap);
         }
 
         data.items[self.index as usize].icon.clone_from(&self.icon);
 
         recalculate(data);
+        Ok(())
     }
 }
 
@@ -150,11 +210,14 @@ impl MenuItem {
             top: 0,
             right: 0,
             bottom: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
         }
     }
 }
@@ -175,11 +238,14 @@ impl MenuItem {
             top: 0,
             right: 0,
             bottom: 0,
-            checked,
+            check_state: checked.into(),
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
         }
     }
 
@@ -198,16 +264,28 @@ impl MenuItem {
             top: 0,
             right: 0,
             bottom: 0,
-            checked,
+            check_state: checked.into(),
             disabled,
             visible: true,
             items: None,
             icon,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
         }
     }
 
     pub fn set_checked(&mut self, checked: bool) {
-        self.checked = checked;
+        self.set_check_state(checked.into());
+    }
+
+    /// Sets the check state. Mixed is only for checkbox.
+    pub fn set_check_state(&mut self, check_state: CheckState) {
+        if check_state == CheckState::Mixed && self.menu_item_type != MenuItemType::Checkbox {
+            return;
+        }
+
+        self.check_state = check_state;
 
         /* Exit if window is not created */
         if self.menu_window_handle == 0 {
@@ -217,7 +295,7 @@ impl MenuItem {
         let data = get_menu_data_mut(self.menu_window_handle);
         let index = self.index as usize;
         if data.items[index].menu_item_type == MenuItemType::Checkbox {
-            data.items[index].checked = checked;
+            data.items[index].check_state = check_state;
         }
         if data.items[index].menu_item_type == MenuItemType::Radio {
             toggle_radio(data, index);
@@ -257,11 +335,14 @@ impl MenuItem {
             top: 0,
             right: 0,
             bottom: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled: false,
             visible: true,
             items: None,
             icon: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
         }
     }
 
@@ -280,11 +361,27 @@ impl MenuItem {
             top: 0,
             right: 0,
             bottom: 0,
-            checked: false,
+            check_state: CheckState::Unchecked,
             disabled: false,
             visible: true,
             items: None,
             icon: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
+        }
+    }
+
+    pub fn new_header(label: &str) -> Self {
+        Self::new_header_with_id("", label)
+    }
+
+    pub fn new_header_with_id(id: &str, label: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            label: label.to_string(),
+            menu_item_type: MenuItemType::Header,
+            ..Self::new_separator()
         }
     }
 }
@@ -313,8 +410,8 @@ impl MenuItemBuilder {
         self
     }
 
-    pub fn accelerator(mut self, accelerator: &str) -> Self {
-        self.menu_item.accelerator = accelerator.to_string();
+    pub fn accelerator(mut self, accelerator: impl Into<String>) -> Self {
+        self.menu_item.accelerator = accelerator.into();
         self
     }
 
@@ -331,7 +428,13 @@ impl MenuItemBuilder {
     }
 
     pub fn checked(mut self, checked: bool) -> Self {
-        self.menu_item.checked = checked;
+        self.menu_item.check_state = checked.into();
+        self
+    }
+
+    /// Check state of a checkbox including the mixed state.
+    pub fn check_state(mut self, check_state: CheckState) -> Self {
+        self.menu_item.check_state = check_state;
         self
     }
 
@@ -350,6 +453,24 @@ impl MenuItemBuilder {
         self
     }
 
+    /// Words matched by search besides the label.
+    pub fn keywords(mut self, keywords: &[&str]) -> Self {
+        self.menu_item.keywords = keywords.iter().map(|keyword| keyword.to_string()).collect();
+        self
+    }
+
+    /// Text shown on hover, also for disabled MenuItems.
+    pub fn tooltip(mut self, tooltip: &str) -> Self {
+        self.menu_item.tooltip = Some(tooltip.to_string());
+        self
+    }
+
+    /// Second line shown under the label in smaller and dimmer text.
+    pub fn description(mut self, description: &str) -> Self {
+        self.menu_item.description = Some(description.to_string());
+        self
+    }
+
     /// Build the [`MenuItem`].
     pub fn build(self) -> MenuItem {
         self.menu_item
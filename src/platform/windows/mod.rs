@@ -3,28 +3,38 @@ mod builder;
 mod image;
 mod menu_item;
 mod util;
-use crate::{config::*, InnerMenuEvent, MenuEvent, MenuItemType, MenuType, ThemeChangeFactor};
+use crate::{
+    accelerator::display_accelerator,
+    config::*,
+    mnemonic::parse_mnemonic,
+    popup_channel, remove_subscribers,
+    validation::{collect_ids, validate_items, validate_new_accelerators},
+    AcceleratorConflict, ActivationSource, CheckState, CloseReason, DismissReason, MenuEvent, MenuItemType, MenuType, PopupResult, PopupSender, ThemeChangeFactor,
+};
+#[cfg(feature = "accelerator")]
+use crate::{accelerator::ChordStep, AcceleratorChord};
 #[cfg(feature = "accelerator")]
-use accelerator::{create_haccel, destroy_haccel, translate_accel};
+use accelerator::{create_haccel, destroy_haccel, is_accelerator_pressed, translate_accel};
 pub use builder::*;
 use image::{
-    colorref_to_d2d1_color_f, create_menu_image, create_write_factory, get_device_context, get_icon_size, get_text_format, set_svg_color, to_2d_rect, MenuImageType, SvgDocument, TextAlignment,
+    colorref_to_d2d1_color_f, create_menu_image, create_write_factory, get_description_text_format, get_device_context, get_header_text_format, get_icon_size, get_text_format, set_svg_color,
+    to_2d_rect, MenuImageType, SvgDocument, TextAlignment,
 };
 pub use menu_item::*;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "accelerator")]
 use std::rc::Rc;
-use std::{ffi::c_void, mem::size_of};
+use std::{collections::HashSet, ffi::c_void, mem::size_of};
 use util::*;
 #[cfg(feature = "accelerator")]
-use windows::Win32::UI::WindowsAndMessaging::{MSG, WM_COMMAND, WM_SYSCOMMAND};
+use windows::Win32::UI::WindowsAndMessaging::{MSG, WM_COMMAND, WM_SYSCOMMAND, WM_SYSKEYDOWN};
 use windows::{
-    core::{w, Error, PCWSTR},
+    core::{w, Error, PCWSTR, PWSTR},
     Win32::{
         Foundation::{HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM},
         Graphics::{
-            Direct2D::{ID2D1Bitmap1, D2D1_BITMAP_INTERPOLATION_MODE_LINEAR, D2D1_DRAW_TEXT_OPTIONS_NONE, D2D1_ROUNDED_RECT},
-            DirectWrite::{DWRITE_MEASURING_MODE_NATURAL, DWRITE_TEXT_ALIGNMENT_TRAILING},
+            Direct2D::{Common::D2D_RECT_F, ID2D1Bitmap1, D2D1_BITMAP_INTERPOLATION_MODE_LINEAR, D2D1_DRAW_TEXT_OPTIONS_NONE, D2D1_ROUNDED_RECT},
+            DirectWrite::{DWRITE_MEASURING_MODE_NATURAL, DWRITE_TEXT_ALIGNMENT_TRAILING, DWRITE_TEXT_METRICS, DWRITE_TEXT_RANGE},
             Gdi::{
                 BeginPaint, ClientToScreen, EndPaint, GetMonitorInfoW, GetWindowDC, InvalidateRect, MonitorFromPoint, MonitorFromWindow, PtInRect, ReleaseDC, ScreenToClient, UpdateWindow, HBRUSH,
                 HDC, MONITORINFO, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTONULL, PAINTSTRUCT,
@@ -35,19 +45,22 @@ use windows::{
             Threading::{AttachThreadInput, GetCurrentThreadId},
         },
         UI::{
+            Controls::{
+                TOOLTIPS_CLASSW, TTF_ABSOLUTE, TTF_TRACK, TTM_ADDTOOLW, TTM_SETMAXTIPWIDTH, TTM_TRACKACTIVATE, TTM_TRACKPOSITION, TTM_UPDATETIPTEXTW, TTS_ALWAYSTIP, TTS_NOPREFIX, TTTOOLINFOW,
+            },
             Input::KeyboardAndMouse::{
-                EnableWindow, GetCapture, GetFocus, ReleaseCapture, SendInput, SetActiveWindow, SetCapture, SetFocus, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_ABSOLUTE, MOUSEEVENTF_LEFTDOWN,
-                MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_VIRTUALDESK, MOUSEINPUT, VIRTUAL_KEY, VK_ESCAPE, VK_LWIN, VK_MENU, VK_RWIN,
+                EnableWindow, GetCapture, GetFocus, GetKeyState, ReleaseCapture, SendInput, SetActiveWindow, SetCapture, SetFocus, INPUT, INPUT_0, INPUT_MOUSE, MOUSEEVENTF_ABSOLUTE,
+                MOUSEEVENTF_LEFTDOWN, MOUSEEVENTF_RIGHTDOWN, MOUSEEVENTF_VIRTUALDESK, MOUSEINPUT, VIRTUAL_KEY, VK_CONTROL, VK_ESCAPE, VK_LWIN, VK_MENU, VK_RWIN,
             },
             Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
             WindowsAndMessaging::{
                 AnimateWindow, CallNextHookEx, CreateWindowExW, DefWindowProcW, DestroyWindow, GetAncestor, GetClientRect, GetCursorPos, GetParent, GetPropW, GetWindow, GetWindowRect,
-                GetWindowThreadProcessId, IsWindow, IsWindowVisible, KillTimer, LoadCursorW, PostMessageW, RegisterClassExW, RemovePropW, SetCursor, SetForegroundWindow, SetPropW, SetTimer,
-                SetWindowPos, SetWindowsHookExW, ShowWindow, SystemParametersInfoW, UnhookWindowsHookEx, WindowFromPoint, AW_BLEND, CS_DROPSHADOW, CS_HREDRAW, CS_VREDRAW, GA_ROOTOWNER, GW_OWNER,
-                HCURSOR, HHOOK, HICON, HWND_TOP, IDC_ARROW, SPI_GETMENUSHOWDELAY, SWP_ASYNCWINDOWPOS, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOOWNERZORDER, SWP_NOSIZE, SWP_NOZORDER,
-                SW_HIDE, SW_SHOWNOACTIVATE, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, TIMERPROC, WH_KEYBOARD, WH_MOUSE, WM_ACTIVATE, WM_APP, WM_CLOSE, WM_DESTROY, WM_ERASEBKGND, WM_KEYDOWN,
-                WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_PAINT, WM_PRINTCLIENT, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SETTINGCHANGE, WM_THEMECHANGED, WNDCLASSEXW, WS_CLIPSIBLINGS,
-                WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_POPUP,
+                GetWindowThreadProcessId, IsWindow, IsWindowVisible, KillTimer, LoadCursorW, PostMessageW, RegisterClassExW, RemovePropW, SendMessageW, SetCursor, SetForegroundWindow, SetPropW,
+                SetTimer, SetWindowPos, SetWindowsHookExW, ShowWindow, SystemParametersInfoW, UnhookWindowsHookEx, WindowFromPoint, AW_BLEND, CS_DROPSHADOW, CS_HREDRAW, CS_VREDRAW, CW_USEDEFAULT,
+                GA_ROOTOWNER, GW_OWNER, HCURSOR, HHOOK, HICON, HWND_TOP, IDC_ARROW, SPI_GETMENUSHOWDELAY, SPI_GETMOUSEHOVERTIME, SWP_ASYNCWINDOWPOS, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE,
+                SWP_NOOWNERZORDER, SWP_NOSIZE, SWP_NOZORDER, SW_HIDE, SW_SHOWNOACTIVATE, SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS, TIMERPROC, WH_KEYBOARD, WH_MOUSE, WINDOW_STYLE, WM_ACTIVATE, WM_APP,
+                WM_CLOSE, WM_DESTROY, WM_ERASEBKGND, WM_KEYDOWN, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE, WM_MOUSEWHEEL, WM_PAINT, WM_PRINTCLIENT, WM_RBUTTONDOWN, WM_RBUTTONUP, WM_SETTINGCHANGE,
+                WM_THEMECHANGED, WNDCLASSEXW, WS_CLIPSIBLINGS, WS_EX_LAYERED, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_POPUP,
             },
         },
     },
@@ -59,6 +72,8 @@ const HOOK_PROP_NAME: &str = "WCPOPUP_KEYBOARD_HOOK";
 pub(crate) const CORNER_RADIUS: i32 = 8;
 const SHOW_SUBMENU_TIMER_ID: usize = 500;
 const HIDE_SUBMENU_TIMER_ID: usize = 501;
+const TOOLTIP_TIMER_ID: usize = 502;
+const TOOLTIP_MAX_WIDTH: isize = 400;
 const FADE_EFFECT_TIME: u32 = 120;
 
 const WM_INACTIVATE: u32 = WM_APP + 0x0004;
@@ -74,6 +89,12 @@ pub struct Menu {
     parent_window_handle: isize,
 }
 
+impl From<Error> for crate::Error {
+    fn from(e: Error) -> Self {
+        crate::Error::Platform(Box::new(e))
+    }
+}
+
 impl Default for Menu {
     fn default() -> Self {
         Self {
@@ -129,6 +150,10 @@ impl Menu {
         create_menu_window(parent).unwrap()
     }
 
+    pub(crate) fn main_menu_handle(&self) -> isize {
+        get_main_window_handle(self.window_handle)
+    }
+
     pub fn config(&self) -> Config {
         get_menu_data(self.window_handle).config.clone()
     }
@@ -170,19 +195,27 @@ impl Menu {
     }
 
     /// Adds a MenuItem to the end of MenuItems.
-    pub fn append(&mut self, item: MenuItem) {
-        self.add_item(item, None);
+    pub fn append(&mut self, item: MenuItem) -> Result<(), crate::Error> {
+        self.add_item(item, None)
     }
 
     /// Adds a MenuItem at the specified index.
-    pub fn insert(&mut self, item: MenuItem, index: u32) {
-        self.add_item(item, Some(index as usize));
+    pub fn insert(&mut self, item: MenuItem, index: u32) -> Result<(), crate::Error> {
+        self.add_item(item, Some(index as usize))
     }
 
-    fn add_item(&mut self, mut item: MenuItem, index: Option<usize>) {
+    fn add_item(&mut self, mut item: MenuItem, index: Option<usize>) -> Result<(), crate::Error> {
+        let main_menu_data = get_menu_data(self.main_menu_handle());
+        let mut ids = HashSet::new();
+        collect_ids(&main_menu_data.items, &mut ids);
+        validate_items(std::slice::from_ref(&item), &mut ids)?;
+        let mut warnings = Vec::new();
+        validate_new_accelerators(&main_menu_data.items, &item, main_menu_data.config.accelerator_conflict, &mut warnings)?;
+        get_menu_data_mut(self.main_menu_handle()).accelerator_conflicts.extend(warnings);
+
         let data = get_menu_data_mut(self.window_handle);
         if item.menu_item_type == MenuItemType::Submenu {
-            self.create_submenu(data, &mut item);
+            self.create_submenu(data, &mut item)?;
         }
 
         #[cfg(feature = "accelerator")]
@@ -205,16 +238,19 @@ impl Menu {
         } else if let Some(last_item) = data.items.last() {
             Self::reset_radio(data, last_item.clone());
         }
+
+        Ok(())
     }
 
-    fn create_submenu(&mut self, data: &MenuData, item: &mut MenuItem) {
+    fn create_submenu(&mut self, data: &MenuData, item: &mut MenuItem) -> Result<(), crate::Error> {
         let builder = MenuBuilder::new_for_submenu(self, &data.config, data.current_theme, item.items.as_mut().unwrap());
-        let memnu = builder.build().unwrap();
+        let memnu = builder.build()?;
         item.submenu = Some(memnu);
+        Ok(())
     }
 
     fn reset_radio(data: &mut MenuData, new_item: MenuItem) {
-        if new_item.menu_item_type == MenuItemType::Radio && new_item.checked {
+        if new_item.menu_item_type == MenuItemType::Radio && new_item.check_state.is_checked() {
             toggle_radio(data, new_item.index as usize);
         }
     }
@@ -307,8 +343,17 @@ impl Menu {
         let _ = unsafe { SetWindowSubclass(parent, Some(menu_owner_subclass_proc), id, Box::into_raw(Box::new(hwnd)) as usize) };
     }
 
-    fn start_popup(&self, x: i32, y: i32, attach_thread: bool) {
+    fn start_popup(&self, x: i32, y: i32, attach_thread: bool, sender: Option<PopupSender>) {
+        /* Finish the current popup so that its result never leaks into the new one */
+        if unsafe { IsWindowVisible(hwnd!(self.window_handle)) }.as_bool() {
+            init_menu_data(self.window_handle, false);
+            post_message(self.window_handle, PopupResult::Dismissed(DismissReason::Replaced));
+        }
+
         let data = get_menu_data_mut(self.window_handle);
+        data.popup_sender = sender;
+        #[cfg(feature = "accelerator")]
+        data.chord_state.reset();
 
         let hwnd = hwnd!(self.window_handle);
         let parent = hwnd!(self.parent_window_handle);
@@ -355,28 +400,135 @@ impl Menu {
         data.popup_info = Some(info);
     }
 
+    /// Returns the accelerator conflicts found under AcceleratorConflictPolicy::Warn and clears them.
+    pub fn take_accelerator_conflicts(&self) -> Vec<AcceleratorConflict> {
+        std::mem::take(&mut get_menu_data_mut(self.main_menu_handle()).accelerator_conflicts)
+    }
+
     /// Shows Menu at the specified point.
     pub fn popup_at(&self, x: i32, y: i32) {
-        self.start_popup(x, y, false);
+        self.start_popup(x, y, false, None);
 
         animate_show_window(self.window_handle);
         set_capture(self.window_handle);
+        MenuEvent::send(self.main_menu_handle(), MenuEvent::Opened);
     }
 
-    /// Shows Menu asynchronously at the specified point and returns the selected MenuItem if any.
-    pub async fn popup_at_async(&self, x: i32, y: i32) -> Option<MenuItem> {
-        self.start_popup(x, y, true);
+    /// Shows Menu asynchronously at the specified point and returns how the popup ended.
+    pub async fn popup_at_async(&self, x: i32, y: i32) -> PopupResult {
+        let (tx, rx) = popup_channel();
+        self.start_popup(x, y, true, Some(tx));
 
         animate_show_window(self.window_handle);
         set_capture(self.window_handle);
+        MenuEvent::send(self.main_menu_handle(), MenuEvent::Opened);
 
-        let mut item = None;
+        /* The sender is dropped without result only when Menu is gone */
+        rx.recv().await.unwrap_or(PopupResult::Dismissed(DismissReason::Destroyed))
+    }
+}
+
+impl Menu {
+    /// Returns true while the popup is shown.
+    pub fn is_open(&self) -> bool {
+        unsafe { IsWindowVisible(hwnd!(self.main_menu_handle())) }.as_bool()
+    }
+
+    /// Selects the MenuItem with the specified id in the same way as a click.
+    /// Returns false if the popup is not shown or the MenuItem cannot be selected.
+    pub fn activate_by_id(&self, id: &str) -> bool {
+        let Some((window_handle, index)) = self.find_operable_item(id) else {
+            return false;
+        };
 
-        if let Ok(event) = MenuEvent::innner_receiver().recv().await {
-            item = event.item;
+        let data = get_menu_data_mut(window_handle);
+        if !data.items[index].menu_item_type.is_selectable() || !on_menu_item_selected(data, index) {
+            return false;
         }
 
-        item
+        let menu_item = data.items[index].clone();
+        let main_window_handle = self.main_menu_handle();
+        init_menu_data(main_window_handle, true);
+        post_message_from(main_window_handle, PopupResult::Selected(menu_item), ActivationSource::Program);
+
+        true
+    }
+
+    /// Binds the accelerators of MenuItems as window shortcuts which work while Menu is closed and the window has focus.
+    /// Activations are notified as MenuEvent::Selected with ActivationSource::Shortcut. Hidden or disabled MenuItems are ignored.
+    #[cfg(feature = "accelerator")]
+    pub fn set_window_shortcuts(&self, enabled: bool) {
+        get_menu_data_mut(self.main_menu_handle()).window_shortcuts = enabled;
+    }
+
+    /// Selects the MenuItem with the specified id in the same way as a pointer hover.
+    /// Returns false if the popup is not shown or the MenuItem cannot be highlighted.
+    pub fn highlight(&self, id: &str) -> bool {
+        let Some((window_handle, index)) = self.find_operable_item(id) else {
+            return false;
+        };
+
+        /* MenuItem in a hidden submenu cannot be hovered */
+        if unsafe { !IsWindowVisible(hwnd!(window_handle)) }.as_bool() {
+            return false;
+        }
+
+        let data = get_menu_data_mut(window_handle);
+        if !data.items[index].menu_item_type.is_selectable() {
+            return false;
+        }
+
+        if select_index(data, window_handle, index as i32) {
+            toggle_submenu(window_handle, data);
+        }
+
+        true
+    }
+
+    /// Closes the popup in the same way as the Escape key.
+    pub fn dismiss(&self) {
+        if self.is_open() {
+            let main_window_handle = self.main_menu_handle();
+            init_menu_data(main_window_handle, true);
+            post_message(main_window_handle, PopupResult::Dismissed(DismissReason::Escape));
+        }
+    }
+
+    /// Closes the popup and its submenus. A pending popup is resolved as [`DismissReason::Closed`].
+    pub fn close(&self) {
+        if self.is_open() {
+            let main_window_handle = self.main_menu_handle();
+            init_menu_data(main_window_handle, true);
+            post_message(main_window_handle, PopupResult::Dismissed(DismissReason::Closed));
+        }
+    }
+
+    fn find_operable_item(&self, id: &str) -> Option<(isize, usize)> {
+        if !self.is_open() {
+            return None;
+        }
+
+        let menu_item = find_by_id(get_menu_data(self.main_menu_handle()), id)?;
+
+        /* Walk up to the main menu since the MenuItem cannot be reached through a disabled or hidden submenu */
+        let mut item = menu_item.clone();
+        loop {
+            if item.disabled || !item.visible {
+                return None;
+            }
+
+            let data = get_menu_data(item.menu_window_handle);
+            if data.menu_type == MenuType::Main {
+                break;
+            }
+
+            /* "parent" of submenu data is its parent Menu's window handle */
+            let submenu_window_handle = item.menu_window_handle;
+            item = get_menu_data(data.parent).items.iter().find(|parent_item| parent_item.submenu.as_ref().is_some_and(|submenu| submenu.window_handle == submenu_window_handle))?.clone();
+        }
+
+        let index = get_menu_data(menu_item.menu_window_handle).items.iter().position(|item| item.uuid == menu_item.uuid)?;
+        Some((menu_item.menu_window_handle, index))
     }
 }
 
@@ -477,6 +629,15 @@ fn cleanup(window: HWND) {
         destroy_haccel(data);
     }
 
+    if data.menu_type == MenuType::Main {
+        /* Menu is closed while shown */
+        if unsafe { IsWindowVisible(window) }.as_bool() {
+            init_menu_data(vtoi!(window.0), false);
+            post_message(vtoi!(window.0), PopupResult::Dismissed(DismissReason::Destroyed));
+        }
+        remove_subscribers(vtoi!(window.0));
+    }
+
     let _ = unsafe { Box::from_raw(data) };
 
     clear_userdata(window)
@@ -487,7 +648,7 @@ unsafe extern "system" fn default_window_proc(window: HWND, msg: u32, wparam: WP
         WM_INACTIVATE => {
             if IsWindowVisible(window).as_bool() {
                 init_menu_data(vtoi!(window.0), true);
-                post_message(None);
+                post_message(vtoi!(window.0), PopupResult::Dismissed(DismissReason::FocusLost));
             }
 
             LRESULT(0)
@@ -520,11 +681,26 @@ unsafe extern "system" fn default_window_proc(window: HWND, msg: u32, wparam: WP
 
         WM_KEYDOWN => {
             if IsWindowVisible(window).as_bool() {
-                let should_close_menu = matches!(VIRTUAL_KEY(wparam.0 as u16), VK_ESCAPE | VK_LWIN | VK_RWIN | VK_MENU);
+                /* Keyboard hook posts key releases as well */
+                #[cfg(feature = "accelerator")]
+                if (lparam.0 >> 31) & 1 == 0 && press_chord(vtoi!(window.0), VIRTUAL_KEY(wparam.0 as u16)) {
+                    return LRESULT(0);
+                }
 
-                if should_close_menu {
+                let maybe_dismiss_reason = match VIRTUAL_KEY(wparam.0 as u16) {
+                    VK_ESCAPE => Some(DismissReason::Escape),
+                    /* Windows key and Alt key move focus to Start menu or menu bar */
+                    VK_LWIN | VK_RWIN | VK_MENU => Some(DismissReason::FocusLost),
+                    _ => None,
+                };
+
+                if let Some(reason) = maybe_dismiss_reason {
                     init_menu_data(vtoi!(window.0), true);
-                    post_message(None);
+                    post_message(vtoi!(window.0), PopupResult::Dismissed(reason));
+                    return LRESULT(0);
+                }
+
+                if (lparam.0 >> 31) & 1 == 0 && press_mnemonic(vtoi!(window.0), VIRTUAL_KEY(wparam.0 as u16)) {
                     return LRESULT(0);
                 }
 
@@ -558,7 +734,7 @@ unsafe extern "system" fn default_window_proc(window: HWND, msg: u32, wparam: WP
                     if on_menu_item_selected(data, index) {
                         init_menu_data(vtoi!(window.0), true);
                         let menu_item = &data.items[index];
-                        post_message(Some(menu_item));
+                        post_message(vtoi!(window.0), PopupResult::Accelerator(menu_item.clone()));
                     }
                 }
             }
@@ -588,7 +764,7 @@ unsafe extern "system" fn default_window_proc(window: HWND, msg: u32, wparam: WP
         WM_MOUSEWHEEL => {
             if IsWindowVisible(window).as_bool() {
                 init_menu_data(vtoi!(window.0), true);
-                post_message(None);
+                post_message(vtoi!(window.0), PopupResult::Dismissed(DismissReason::Scroll));
             }
             LRESULT(0)
         }
@@ -634,7 +810,7 @@ fn on_mouse_down(window: HWND, msg: u32) {
 
         /* Close menu */
         init_menu_data(vtoi!(window.0), false);
-        post_message(None);
+        post_message(vtoi!(window.0), PopupResult::Dismissed(DismissReason::ClickOutside));
 
         /* If mouse input occurs on parent window, send mouse input */
         send_mouse_input(window, msg);
@@ -653,23 +829,61 @@ fn on_mouse_up(window: HWND) {
         if on_menu_item_selected(data, index as usize) {
             let menu_item = &data.items[index as usize];
             init_menu_data(vtoi!(window.0), true);
-            post_message(Some(menu_item));
+            post_message(vtoi!(window.0), PopupResult::Selected(menu_item.clone()));
         }
     }
 }
 
-fn post_message(menu_item: Option<&MenuItem>) {
-    if let Some(item) = menu_item {
-        MenuEvent::send(MenuEvent {
+fn post_message(window_handle: isize, result: PopupResult) {
+    let source = match result {
+        PopupResult::Accelerator(_) => ActivationSource::Accelerator,
+        _ => ActivationSource::Menu,
+    };
+    post_message_from(window_handle, result, source);
+}
+
+fn post_message_from(window_handle: isize, result: PopupResult, source: ActivationSource) {
+    let main_window_handle = get_main_window_handle(window_handle);
+
+    if let Some(item) = result.item() {
+        MenuEvent::send(
+            main_window_handle,
+            MenuEvent::Selected {
+                item: item.clone(),
+                source,
+            },
+        );
+    }
+
+    MenuEvent::send(
+        main_window_handle,
+        MenuEvent::Closed {
+            reason: CloseReason::from(&result),
+        },
+    );
+
+    if let Some(sender) = get_menu_data_mut(main_window_handle).popup_sender.take() {
+        let _ = sender.try_send(result);
+    }
+}
+
+fn send_toggled(item: &MenuItem) {
+    MenuEvent::send(
+        get_main_window_handle(item.menu_window_handle),
+        MenuEvent::Toggled {
             item: item.clone(),
-        });
-        MenuEvent::send_inner(InnerMenuEvent {
-            item: Some(item.clone()),
-        });
-    } else {
-        MenuEvent::send_inner(InnerMenuEvent {
-            item: None,
-        });
+            check_state: item.check_state,
+        },
+    );
+}
+
+fn send_submenu_closed(submenu_window_handle: isize) {
+    /* "parent" of submenu data is its parent Menu's window handle */
+    let parent_window_handle = get_menu_data(submenu_window_handle).parent;
+    let parent_data = get_menu_data(parent_window_handle);
+    let maybe_item = parent_data.items.iter().find(|item| item.submenu.as_ref().is_some_and(|submenu| submenu.window_handle == submenu_window_handle));
+    if let Some(item) = maybe_item {
+        MenuEvent::send(get_main_window_handle(parent_window_handle), MenuEvent::SubmenuClosed(item.clone()));
     }
 }
 
@@ -690,13 +904,67 @@ fn on_menu_item_selected(data: &mut MenuData, index: usize) -> bool {
     }
 
     /* Toggle radio checkbox */
-    if data.items[index].menu_item_type == MenuItemType::Radio {
+    if data.items[index].menu_item_type == MenuItemType::Radio && !data.items[index].check_state.is_checked() {
+        let name = data.items[index].name.clone();
+        let maybe_unchecked_index = data.items.iter().position(|item| item.menu_item_type == MenuItemType::Radio && item.name == name && item.check_state.is_checked());
         toggle_radio(data, index);
+        if let Some(unchecked_index) = maybe_unchecked_index {
+            send_toggled(&data.items[unchecked_index]);
+        }
+        send_toggled(&data.items[index]);
     }
 
-    /* Toggle checkbox */
+    /* Toggle checkbox. Mixed is unchecked, so clicking it checks the MenuItem */
     if data.items[index].menu_item_type == MenuItemType::Checkbox {
-        data.items[index].checked = !data.items[index].checked;
+        data.items[index].check_state = (!data.items[index].check_state.is_checked()).into();
+        send_toggled(&data.items[index]);
+    }
+
+    true
+}
+
+/// Selects the MenuItem of the mnemonic in the innermost shown Menu. Returns true if the key is consumed.
+fn press_mnemonic(window_handle: isize, virtual_key: VIRTUAL_KEY) -> bool {
+    /* Keys with Ctrl or Alt are left to accelerators */
+    if unsafe { GetKeyState(VK_CONTROL.0 as i32) } < 0 || unsafe { GetKeyState(VK_MENU.0 as i32) } < 0 {
+        return false;
+    }
+
+    /* Virtual key codes of letters and digits are their ASCII codes */
+    let Some(key) = char::from_u32(virtual_key.0 as u32).filter(char::is_ascii_alphanumeric).map(|c| c.to_ascii_lowercase()) else {
+        return false;
+    };
+
+    let mut menu_window_handle = window_handle;
+    loop {
+        let data = get_menu_data(menu_window_handle);
+        if data.visible_submenu_index < 0 {
+            break;
+        }
+
+        let submenu_window_handle = data.items[data.visible_submenu_index as usize].submenu.as_ref().unwrap().window_handle;
+        if unsafe { !IsWindowVisible(hwnd!(submenu_window_handle)) }.as_bool() {
+            break;
+        }
+        menu_window_handle = submenu_window_handle;
+    }
+
+    let data = get_menu_data_mut(menu_window_handle);
+    let maybe_index = data.items.iter().position(|item| item.visible && !item.disabled && item.menu_item_type.is_selectable() && parse_mnemonic(&item.label, data.config.mnemonic).key() == Some(key));
+    let Some(index) = maybe_index else {
+        return false;
+    };
+
+    if data.items[index].menu_item_type == MenuItemType::Submenu {
+        select_index(data, menu_window_handle, index as i32);
+        toggle_submenu(menu_window_handle, data);
+        return true;
+    }
+
+    if on_menu_item_selected(data, index) {
+        let menu_item = data.items[index].clone();
+        init_menu_data(window_handle, true);
+        post_message(window_handle, PopupResult::Selected(menu_item));
     }
 
     true
@@ -775,10 +1043,96 @@ unsafe extern "system" fn menu_owner_subclass_proc(window: HWND, msg: u32, wpara
             DefSubclassProc(window, msg, wparam, lparam)
         }
 
+        #[cfg(feature = "accelerator")]
+        WM_KEYDOWN | WM_SYSKEYDOWN => {
+            let hwnd_ptr = dwrefdata as *const HWND;
+            let hwnd = unsafe { *hwnd_ptr };
+            if IsWindow(Some(hwnd)).as_bool() && !IsWindowVisible(hwnd).as_bool() && activate_shortcut(vtoi!(hwnd.0), VIRTUAL_KEY(wparam.0 as u16)) {
+                return LRESULT(0);
+            }
+            DefSubclassProc(window, msg, wparam, lparam)
+        }
+
         _ => DefSubclassProc(window, msg, wparam, lparam),
     }
 }
 
+#[cfg(feature = "accelerator")]
+fn activate_shortcut(window_handle: isize, virtual_key: VIRTUAL_KEY) -> bool {
+    let data = get_menu_data_mut(window_handle);
+    if !data.window_shortcuts {
+        return false;
+    }
+
+    let mut chords = Vec::new();
+    collect_chords(data, &mut chords);
+    let uuid = match data.chord_state.press(|accelerator| is_accelerator_pressed(accelerator, virtual_key), chords) {
+        ChordStep::Matched(uuid) => uuid,
+        ChordStep::Pending | ChordStep::Cancelled => return true,
+        ChordStep::Unmatched => return false,
+    };
+
+    let Some((data, index)) = index_of_item(data, uuid) else {
+        return true;
+    };
+
+    if on_menu_item_selected(data, index) {
+        /* Popup is not shown, so only notify the selection */
+        MenuEvent::send(
+            window_handle,
+            MenuEvent::Selected {
+                item: data.items[index].clone(),
+                source: ActivationSource::Shortcut,
+            },
+        );
+    }
+
+    true
+}
+
+/// Handles chords while Menu is shown. Returns true if the key is consumed.
+/// Single key accelerators are handled by the accelerator table.
+#[cfg(feature = "accelerator")]
+fn press_chord(window_handle: isize, virtual_key: VIRTUAL_KEY) -> bool {
+    let data = get_menu_data_mut(window_handle);
+
+    let mut chords = Vec::new();
+    collect_chords(data, &mut chords);
+    chords.retain(|(chord, _)| chord.is_sequence());
+    let uuid = match data.chord_state.press(|accelerator| is_accelerator_pressed(accelerator, virtual_key), chords) {
+        ChordStep::Matched(uuid) => uuid,
+        ChordStep::Pending | ChordStep::Cancelled => return true,
+        ChordStep::Unmatched => return false,
+    };
+
+    if let Some((data, index)) = index_of_item(data, uuid) {
+        if on_menu_item_selected(data, index) {
+            let menu_item = data.items[index].clone();
+            init_menu_data(window_handle, true);
+            post_message(window_handle, PopupResult::Accelerator(menu_item));
+        }
+    }
+
+    true
+}
+
+/// Collects the accelerators of visible and enabled MenuItems including submenus.
+#[cfg(feature = "accelerator")]
+fn collect_chords(data: &MenuData, chords: &mut Vec<(AcceleratorChord, u16)>) {
+    for item in &data.items {
+        if !item.visible || item.disabled {
+            continue;
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            let submenu_window_handle = item.submenu.as_ref().unwrap().window_handle;
+            collect_chords(get_menu_data(submenu_window_handle), chords);
+        } else if let Ok(chord) = item.accelerator.parse::<AcceleratorChord>() {
+            chords.push((chord, item.uuid));
+        }
+    }
+}
+
 fn finish_popup(info: &PopupInfo, should_restore_focus: bool) {
     #[cfg(feature = "webview")]
     let _ = unsafe { EnableWindow(hwnd!(info.focus_window_handle), true) };
@@ -786,6 +1140,8 @@ fn finish_popup(info: &PopupInfo, should_restore_focus: bool) {
     let hwnd = hwnd!(info.window_handle);
     let _ = unsafe { ReleaseCapture() };
 
+    hide_tooltip(info.window_handle);
+
     let _ = unsafe { ShowWindow(hwnd, SW_HIDE) };
 
     let prop_name = encode_wide(HOOK_PROP_NAME);
@@ -805,6 +1161,7 @@ fn finish_popup(info: &PopupInfo, should_restore_focus: bool) {
 }
 
 fn init_menu_data(window_handle: isize, should_restore_focus: bool) {
+    hide_tooltip(window_handle);
     let data = get_menu_data_mut(window_handle);
 
     data.selected_index = -1;
@@ -964,7 +1321,7 @@ fn paint(dc: HDC, data: &MenuData, items: Vec<&MenuItem>) -> Result<(), Error> {
         let whole_item_rect = get_item_rect(item);
 
         let disabled = item.disabled;
-        let checked = item.checked;
+        let check_state = item.check_state;
         let selected = item.index as i32 == data.selected_index && !disabled;
 
         fill_background(data, &whole_item_rect, scheme, selected)?;
@@ -975,6 +1332,16 @@ fn paint(dc: HDC, data: &MenuData, items: Vec<&MenuItem>) -> Result<(), Error> {
                 draw_separator(data, &whole_item_rect, scheme)?;
             }
 
+            MenuItemType::Header => {
+                let item_rect = RECT {
+                    left: whole_item_rect.left + data.config.size.item_horizontal_padding,
+                    top: whole_item_rect.top + data.config.size.item_vertical_padding,
+                    right: whole_item_rect.right - data.config.size.item_horizontal_padding,
+                    bottom: whole_item_rect.bottom - data.config.size.item_vertical_padding,
+                };
+                draw_header(data, item, &item_rect, scheme)?;
+            }
+
             _ => {
                 let item_rect = RECT {
                     left: whole_item_rect.left + data.config.size.item_horizontal_padding,
@@ -983,8 +1350,10 @@ fn paint(dc: HDC, data: &MenuData, items: Vec<&MenuItem>) -> Result<(), Error> {
                     bottom: whole_item_rect.bottom - data.config.size.item_vertical_padding,
                 };
 
-                if checked {
-                    draw_menu_checkmark(data, &item_rect, scheme, disabled)?;
+                if check_state == CheckState::Checked {
+                    draw_menu_checkmark(data, &data.check_icon, &item_rect, scheme, disabled)?;
+                } else if check_state == CheckState::Mixed {
+                    draw_menu_checkmark(data, &data.mixed_icon, &item_rect, scheme, disabled)?;
                 }
 
                 if item.icon.is_some() {
@@ -1043,7 +1412,7 @@ fn draw_svg(data: &MenuData, svg: &SvgDocument, color: u32, item_rect: &RECT, re
     Ok(())
 }
 
-fn draw_menu_checkmark(data: &MenuData, item_rect: &RECT, scheme: &ColorScheme, disabled: bool) -> Result<(), Error> {
+fn draw_menu_checkmark(data: &MenuData, check_icon: &MenuImageType, item_rect: &RECT, scheme: &ColorScheme, disabled: bool) -> Result<(), Error> {
     let space = data.icon_space.left;
     let check_rect = RECT {
         left: item_rect.left + space.lmargin,
@@ -1057,9 +1426,9 @@ fn draw_menu_checkmark(data: &MenuData, item_rect: &RECT, scheme: &ColorScheme,
     } else {
         scheme.color
     };
-    let icon_size = get_icon_size(&data.check_icon);
+    let icon_size = get_icon_size(check_icon);
 
-    match &data.check_icon {
+    match check_icon {
         MenuImageType::Bitmap(bitmap) => draw_bitmap(data, bitmap, item_rect, check_rect, &icon_size),
         MenuImageType::Svg(svg) => draw_svg(data, svg, color, item_rect, check_rect, &icon_size),
     }
@@ -1134,7 +1503,64 @@ fn draw_menu_text(data: &MenuData, item: &MenuItem, item_rect: &RECT, scheme: &C
 
     let brush = unsafe { data.dc_render_target.CreateSolidColorBrush(&colorref_to_d2d1_color_f(color), None) }?;
 
-    unsafe { data.dc_render_target.DrawText(encode_wide(&item.label).as_mut(), &format, &text_2d_rect, &brush, D2D1_DRAW_TEXT_OPTIONS_NONE, DWRITE_MEASURING_MODE_NATURAL) };
+    let mnemonic = parse_mnemonic(&item.label, data.config.mnemonic);
+    let label = encode_wide(&mnemonic.text);
+
+    /* Label and description are centered together, so label is placed at the top of the pair */
+    let description_layout = match &item.description {
+        Some(description) => {
+            let description_format = get_description_text_format(&factory, data.current_theme, &data.config)?;
+            Some(unsafe { factory.CreateTextLayout(&encode_wide(description), &description_format, text_2d_rect.right - text_2d_rect.left, 0.0) }?)
+        }
+        None => None,
+    };
+
+    let label_2d_rect = match &description_layout {
+        Some(description_layout) => {
+            let mut label_metrics = DWRITE_TEXT_METRICS::default();
+            unsafe { factory.CreateTextLayout(&label, &format, text_2d_rect.right - text_2d_rect.left, 0.0)?.GetMetrics(&mut label_metrics) }?;
+            let mut description_metrics = DWRITE_TEXT_METRICS::default();
+            unsafe { description_layout.GetMetrics(&mut description_metrics) }?;
+            let top = text_2d_rect.top + (text_2d_rect.bottom - text_2d_rect.top - label_metrics.height - description_metrics.height) / 2.0;
+            D2D_RECT_F {
+                top,
+                bottom: top + label_metrics.height,
+                ..text_2d_rect
+            }
+        }
+        None => text_2d_rect,
+    };
+
+    /* Underline the mnemonic */
+    let layout = unsafe { factory.CreateTextLayout(&label, &format, label_2d_rect.right - label_2d_rect.left, label_2d_rect.bottom - label_2d_rect.top) }?;
+    if let Some(index) = mnemonic.index {
+        let start: usize = mnemonic.text.chars().take(index).map(char::len_utf16).sum();
+        let length = mnemonic.text.chars().nth(index).map_or(1, char::len_utf16);
+        let range = DWRITE_TEXT_RANGE {
+            startPosition: start as u32,
+            length: length as u32,
+        };
+        unsafe { layout.SetUnderline(true, range) }?;
+    }
+    let origin = Vector2 {
+        X: label_2d_rect.left,
+        Y: label_2d_rect.top,
+    };
+    unsafe { data.dc_render_target.DrawTextLayout(origin, &layout, &brush, D2D1_DRAW_TEXT_OPTIONS_NONE) };
+
+    if let Some(description_layout) = &description_layout {
+        let color = if disabled {
+            scheme.disabled
+        } else {
+            scheme.description
+        };
+        let brush = unsafe { data.dc_render_target.CreateSolidColorBrush(&colorref_to_d2d1_color_f(color), None) }?;
+        let origin = Vector2 {
+            X: label_2d_rect.left,
+            Y: label_2d_rect.bottom,
+        };
+        unsafe { data.dc_render_target.DrawTextLayout(origin, description_layout, &brush, D2D1_DRAW_TEXT_OPTIONS_NONE) };
+    }
 
     if !item.accelerator.is_empty() {
         let color = if disabled {
@@ -1144,12 +1570,24 @@ fn draw_menu_text(data: &MenuData, item: &MenuItem, item_rect: &RECT, scheme: &C
         };
         let brush = unsafe { data.dc_render_target.CreateSolidColorBrush(&colorref_to_d2d1_color_f(color), None) }?;
         unsafe { format.SetTextAlignment(DWRITE_TEXT_ALIGNMENT_TRAILING) }?;
-        unsafe { data.dc_render_target.DrawText(encode_wide(&item.accelerator).as_mut(), &format, &text_2d_rect, &brush, D2D1_DRAW_TEXT_OPTIONS_NONE, DWRITE_MEASURING_MODE_NATURAL) };
+        let accelerator = display_accelerator(&item.accelerator, &data.config.accelerator_format);
+        /* Accelerator is aligned with label */
+        unsafe { data.dc_render_target.DrawText(encode_wide(&accelerator).as_mut(), &format, &label_2d_rect, &brush, D2D1_DRAW_TEXT_OPTIONS_NONE, DWRITE_MEASURING_MODE_NATURAL) };
     }
 
     Ok(())
 }
 
+fn draw_header(data: &MenuData, item: &MenuItem, item_rect: &RECT, scheme: &ColorScheme) -> Result<(), Error> {
+    let factory = create_write_factory()?;
+    let format = get_header_text_format(&factory, data.current_theme, &data.config)?;
+    let brush = unsafe { data.dc_render_target.CreateSolidColorBrush(&colorref_to_d2d1_color_f(scheme.description), None) }?;
+    let label = parse_mnemonic(&item.label, data.config.mnemonic).text;
+    unsafe { data.dc_render_target.DrawText(encode_wide(&label).as_mut(), &format, &to_2d_rect(item_rect), &brush, D2D1_DRAW_TEXT_OPTIONS_NONE, DWRITE_MEASURING_MODE_NATURAL) };
+
+    Ok(())
+}
+
 fn draw_menu_arrow(data: &MenuData, item_rect: &RECT, scheme: &ColorScheme, disabled: bool) -> Result<(), Error> {
     let width = data.icon_space.right.width;
     let arrow_rect = RECT {
@@ -1265,6 +1703,11 @@ fn change_selection(data: &mut MenuData, window_handle: isize, screen_point: POI
         return false;
     }
 
+    select_index(data, window_handle, selected_index)
+}
+
+fn select_index(data: &mut MenuData, window_handle: isize, selected_index: i32) -> bool {
+    let hwnd = hwnd!(window_handle);
     let selection_changed = data.selected_index != selected_index;
 
     if selection_changed {
@@ -1273,6 +1716,7 @@ fn change_selection(data: &mut MenuData, window_handle: isize, screen_point: POI
             if !item.disabled {
                 let rect = get_item_rect(item);
                 let _ = unsafe { InvalidateRect(Some(hwnd), Some(&rect), false) };
+                MenuEvent::send(get_main_window_handle(window_handle), MenuEvent::Highlighted(item.clone()));
             }
         }
 
@@ -1287,6 +1731,10 @@ fn change_selection(data: &mut MenuData, window_handle: isize, screen_point: POI
 
     data.selected_index = selected_index;
 
+    if selection_changed {
+        schedule_tooltip(window_handle, data);
+    }
+
     selection_changed
 }
 
@@ -1367,13 +1815,18 @@ unsafe extern "system" fn delay_show_submenu(hwnd: HWND, _msg: u32, id: usize, _
 
         let _ = SetWindowPos(hwnd!(submenu_window_handle), Some(HWND_TOP), x, y, submenu_data.size.width, submenu_data.size.height, SWP_ASYNCWINDOWPOS | SWP_NOOWNERZORDER | SWP_NOACTIVATE);
         animate_show_window(submenu_window_handle);
+        MenuEvent::send(get_main_window_handle(vtoi!(hwnd.0)), MenuEvent::SubmenuOpened(submenu_item.clone()));
     }
 }
 
 fn hide_submenu(window_handle: isize) {
+    hide_tooltip(window_handle);
     let data = get_menu_data_mut(window_handle);
     data.selected_index = -1;
-    let _ = unsafe { ShowWindow(hwnd!(window_handle), SW_HIDE) };
+    if unsafe { IsWindowVisible(hwnd!(window_handle)) }.as_bool() {
+        let _ = unsafe { ShowWindow(hwnd!(window_handle), SW_HIDE) };
+        send_submenu_closed(window_handle);
+    }
 }
 
 fn animate_hide_submenu(window_handle: isize) {
@@ -1386,9 +1839,111 @@ fn animate_hide_submenu(window_handle: isize) {
 
 unsafe extern "system" fn delay_hide_submenu(hwnd: HWND, _msg: u32, id: usize, _time: u32) {
     let _ = KillTimer(Some(hwnd), id);
+    hide_tooltip(vtoi!(hwnd.0));
     let data = get_menu_data_mut(vtoi!(hwnd.0));
     data.selected_index = -1;
-    let _ = unsafe { ShowWindow(hwnd, SW_HIDE) };
+    if unsafe { IsWindowVisible(hwnd) }.as_bool() {
+        let _ = unsafe { ShowWindow(hwnd, SW_HIDE) };
+        send_submenu_closed(vtoi!(hwnd.0));
+    }
+}
+
+/// Shows the tooltip of the selected MenuItem after the hover time. The current tooltip is hidden first.
+fn schedule_tooltip(window_handle: isize, data: &MenuData) {
+    hide_tooltip(window_handle);
+
+    if data.selected_index < 0 || data.items[data.selected_index as usize].tooltip.is_none() {
+        return;
+    }
+
+    let proc: TIMERPROC = Some(delay_show_tooltip);
+    let mut hover_time: u32 = 0;
+    let _ = unsafe { SystemParametersInfoW(SPI_GETMOUSEHOVERTIME, 0, Some(&mut hover_time as *mut _ as *mut _), SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0)) };
+    unsafe { SetTimer(Some(hwnd!(window_handle)), TOOLTIP_TIMER_ID, hover_time, proc) };
+}
+
+unsafe extern "system" fn delay_show_tooltip(hwnd: HWND, _msg: u32, id: usize, _time: u32) {
+    let _ = KillTimer(Some(hwnd), id);
+
+    let data = get_menu_data_mut(vtoi!(hwnd.0));
+    if data.selected_index < 0 || !IsWindowVisible(hwnd).as_bool() {
+        return;
+    }
+
+    let item = &data.items[data.selected_index as usize];
+    let Some(tooltip) = &item.tooltip else {
+        return;
+    };
+
+    /* Disabled MenuItems are selected without highlight, so their tooltips are shown as well */
+    let text = encode_wide(tooltip);
+    let mut point = POINT {
+        x: item.left,
+        y: item.bottom,
+    };
+    let _ = ClientToScreen(hwnd, &mut point);
+
+    if data.tooltip_window_handle == 0 {
+        let Ok(tooltip_window) = create_tooltip_window(hwnd) else {
+            return;
+        };
+        data.tooltip_window_handle = vtoi!(tooltip_window.0);
+    }
+
+    let tooltip_window = hwnd!(data.tooltip_window_handle);
+    let mut tool_info = get_tool_info(hwnd);
+    tool_info.lpszText = PWSTR(text.as_ptr() as *mut _);
+    SendMessageW(tooltip_window, TTM_UPDATETIPTEXTW, None, Some(LPARAM(&tool_info as *const _ as isize)));
+    SendMessageW(tooltip_window, TTM_TRACKPOSITION, None, Some(LPARAM(((point.y as isize) << 16) | (point.x as isize & 0xFFFF))));
+    SendMessageW(tooltip_window, TTM_TRACKACTIVATE, Some(WPARAM(1)), Some(LPARAM(&tool_info as *const _ as isize)));
+}
+
+fn hide_tooltip(window_handle: isize) {
+    let hwnd = hwnd!(window_handle);
+    let _ = unsafe { KillTimer(Some(hwnd), TOOLTIP_TIMER_ID) };
+
+    let data = get_menu_data(window_handle);
+    if data.tooltip_window_handle != 0 {
+        let tool_info = get_tool_info(hwnd);
+        unsafe { SendMessageW(hwnd!(data.tooltip_window_handle), TTM_TRACKACTIVATE, Some(WPARAM(0)), Some(LPARAM(&tool_info as *const _ as isize))) };
+    }
+}
+
+/// Creates a tracking tooltip owned by the Menu window. It is destroyed together with the Menu window.
+fn create_tooltip_window(hwnd: HWND) -> Result<HWND, Error> {
+    let tooltip_window = unsafe {
+        CreateWindowExW(
+            WS_EX_TOPMOST | WS_EX_TOOLWINDOW,
+            TOOLTIPS_CLASSW,
+            PCWSTR::null(),
+            WS_POPUP | WINDOW_STYLE(TTS_NOPREFIX | TTS_ALWAYSTIP),
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            CW_USEDEFAULT,
+            Some(hwnd),
+            None,
+            GetModuleHandleW(PCWSTR::null()).map(Into::into).ok(),
+            None,
+        )
+    }?;
+
+    let tool_info = get_tool_info(hwnd);
+    unsafe { SendMessageW(tooltip_window, TTM_ADDTOOLW, None, Some(LPARAM(&tool_info as *const _ as isize))) };
+    /* Long text wraps instead of running off the screen */
+    unsafe { SendMessageW(tooltip_window, TTM_SETMAXTIPWIDTH, None, Some(LPARAM(TOOLTIP_MAX_WIDTH))) };
+
+    Ok(tooltip_window)
+}
+
+fn get_tool_info(hwnd: HWND) -> TTTOOLINFOW {
+    TTTOOLINFOW {
+        cbSize: size_of::<TTTOOLINFOW>() as u32,
+        uFlags: TTF_TRACK | TTF_ABSOLUTE,
+        hwnd,
+        uId: 0,
+        ..Default::default()
+    }
 }
 
 fn get_item_rect(item: &MenuItem) -> RECT {
@@ -1427,8 +1982,8 @@ fn index_from_point(hwnd: HWND, screen_pt: POINT, data: &MenuData) -> i32 {
 
     if pt.x >= 0 && pt.x < data.size.width && pt.y >= 0 && pt.y < data.size.height {
         for item in &data.items {
-            /* Ignore invisible */
-            if item.menu_item_type == MenuItemType::Separator || !item.visible {
+            /* Ignore invisible and unselectable */
+            if !item.menu_item_type.is_selectable() || !item.visible {
                 continue;
             }
 
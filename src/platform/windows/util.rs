@@ -1,11 +1,13 @@
 use super::{
     create_write_factory,
-    image::{get_icon_space, get_text_metrics},
+    image::{get_description_metrics, get_header_metrics, get_icon_space, get_text_metrics},
     IconSpace, MenuData, MenuItem, Size, CORNER_RADIUS, DEFAULT_ICON_MARGIN, MIN_BUTTON_WIDTH,
 };
 use crate::{
+    accelerator::display_accelerator,
     config::{hex_from_rgb, rgba_from_hex, ColorScheme, Config, Corner, Theme},
-    MenuItemType,
+    mnemonic::parse_mnemonic,
+    CheckState, MenuItemType, MenuType,
 };
 use std::{
     mem::{size_of, transmute},
@@ -65,6 +67,18 @@ pub(crate) fn get_menu_data<'a>(window_handle: isize) -> &'a MenuData {
     unsafe { &*item_data_ptr }
 }
 
+pub(crate) fn get_main_window_handle(window_handle: isize) -> isize {
+    let mut handle = window_handle;
+    loop {
+        let data = get_menu_data(handle);
+        if data.menu_type == MenuType::Main {
+            return handle;
+        }
+        /* "parent" of submenu data is its parent Menu's window handle */
+        handle = data.parent;
+    }
+}
+
 pub(crate) fn get_menu_data_mut<'a>(window_handle: isize) -> &'a mut MenuData {
     let userdata = unsafe { GetWindowLongPtrW(hwnd!(window_handle), GWL_USERDATA) };
     let item_data_ptr = userdata as *mut MenuData;
@@ -95,11 +109,11 @@ pub(crate) fn HIWORD(dword: u32) -> u16 {
 }
 
 pub(crate) fn toggle_radio(data: &mut MenuData, index: usize) {
-    data.items[index].checked = true;
+    data.items[index].check_state = CheckState::Checked;
 
     for i in 0..data.items.len() {
         if data.items[i].menu_item_type == MenuItemType::Radio && data.items[i].name == data.items[index].name && i != index {
-            data.items[i].checked = false;
+            data.items[i].check_state = CheckState::Unchecked;
         }
     }
 }
@@ -121,16 +135,13 @@ pub(crate) fn calculate(items: &mut [MenuItem], config: &Config, theme: Theme, i
 
     /* Find the widest accelerator string */
     let mut widest_accel = (0.0, "");
-    let mut cloned_items = Vec::new();
-
-    items.clone_into(&mut cloned_items);
-    let accels = cloned_items.iter().map(|i| i.accelerator.as_str());
-    for accel in accels {
+    let accels: Vec<String> = items.iter().map(|item| display_accelerator(&item.accelerator, &config.accelerator_format)).collect();
+    for accel in &accels {
         if !accel.is_empty() {
             let mut raw_text = encode_wide(accel);
             let metrics = get_text_metrics(&factory, theme, config, &mut raw_text)?;
             if metrics.width >= widest_accel.0 {
-                widest_accel = (metrics.width, accel);
+                widest_accel = (metrics.width, accel.as_str());
             }
         }
     }
@@ -199,8 +210,16 @@ pub(crate) fn measure_item(factory: &IDWriteFactory, config: &Config, menu_item:
             height += config.size.item_vertical_padding * 2;
         }
 
+        MenuItemType::Header => {
+            let metrics = get_header_metrics(factory, theme, config, &mut encode_wide(parse_mnemonic(&menu_item.label, config.mnemonic).text))?;
+            height += (metrics.height as i32).abs();
+            height += config.size.item_vertical_padding * 2;
+            width += metrics.width as i32;
+            width += config.size.item_horizontal_padding * 2;
+        }
+
         _ => {
-            let mut raw_text = encode_wide(&menu_item.label);
+            let mut raw_text = encode_wide(parse_mnemonic(&menu_item.label, config.mnemonic).text);
             /* Set widest accelerator string */
             if !widest_accel.is_empty() {
                 raw_text.extend(encode_wide(widest_accel));
@@ -212,6 +231,15 @@ pub(crate) fn measure_item(factory: &IDWriteFactory, config: &Config, menu_item:
             if height < 0 {
                 height = -height;
             }
+            width = metrics.width as i32;
+
+            /* Description is placed under label */
+            if let Some(description) = &menu_item.description {
+                let description_metrics = get_description_metrics(factory, theme, config, &mut encode_wide(description))?;
+                height += (description_metrics.height as i32).abs();
+                width = std::cmp::max(width, description_metrics.width as i32);
+            }
+
             let icon_height = std::cmp::max(icon_space.left.width, icon_space.mid.width);
             let icon_height = std::cmp::max(icon_space.right.width, icon_height);
             if height < icon_height {
@@ -219,7 +247,6 @@ pub(crate) fn measure_item(factory: &IDWriteFactory, config: &Config, menu_item:
             }
             height += config.size.item_vertical_padding * 2;
 
-            width = metrics.width as i32;
             width += config.size.item_horizontal_padding * 2;
             width += icon_space.left.width + icon_space.left.lmargin + icon_space.left.rmargin;
 
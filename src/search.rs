@@ -0,0 +1,195 @@
+//! Fuzzy search over the MenuItems of a Menu.
+//!
+//! [`Menu::search`] flattens the Menu and its submenus into a list of [`SearchMatch`] ordered by score.
+//! Each word of the query must match either the label or one of the keywords of a MenuItem as a case-insensitive subsequence.
+//!
+//! ```no_run
+//! builder.append(MenuItem::builder(MenuItemType::Text).id("pdf").label("PDF").keywords(&["export", "print"]).build());
+//! let matches = menu.search("exp pdf");
+//! ```
+use crate::{mnemonic::parse_mnemonic, validation::submenu_items, Menu, MenuItem, MenuItemType};
+
+/// Separator of the submenu labels in [`SearchMatch::path_text`].
+pub const PATH_SEPARATOR: &str = " › ";
+
+/// MenuItem matching the query.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub item: MenuItem,
+    /// Labels of the submenus containing the MenuItem, outermost first. Empty for MenuItems of the Menu itself.
+    pub path: Vec<String>,
+    /// Higher is better.
+    pub score: u32,
+}
+
+impl SearchMatch {
+    /// Submenu path such as "Export › Image".
+    pub fn path_text(&self) -> String {
+        self.path.join(PATH_SEPARATOR)
+    }
+}
+
+/// Scores the query as a case-insensitive subsequence of the text. Returns None if the text does not contain it.
+/// Characters matched at the start of a word or right after the previous match score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+
+    for c in query.chars().flat_map(char::to_lowercase) {
+        let index = position + text[position..].iter().position(|t| *t == c)?;
+
+        score += 1;
+        if index == 0 || !text[index - 1].is_alphanumeric() {
+            score += 8;
+        }
+        if previous.is_some_and(|previous| previous + 1 == index) {
+            score += 4;
+        }
+
+        previous = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+/// Scores the MenuItem by the best match of each word of the query in its label and keywords.
+fn score_item(item: &MenuItem, query: &str, mnemonic: bool) -> Option<u32> {
+    let label = parse_mnemonic(&item.label, mnemonic).text;
+    query.split_whitespace().try_fold(0, |total, word| {
+        let best = std::iter::once(label.as_str()).chain(item.keywords.iter().map(String::as_str)).filter_map(|text| fuzzy_score(word, text)).max()?;
+        Some(total + best)
+    })
+}
+
+/// Collects visible and enabled MenuItems matching the query. Submenus are searched but not matched themselves.
+fn collect_matches(items: &[MenuItem], query: &str, mnemonic: bool, path: &mut Vec<String>, matches: &mut Vec<SearchMatch>) {
+    for item in items {
+        if !item.visible || item.disabled || !item.menu_item_type.is_selectable() {
+            continue;
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            path.push(parse_mnemonic(&item.label, mnemonic).text);
+            collect_matches(&submenu_items(item), query, mnemonic, path, matches);
+            path.pop();
+        } else if let Some(score) = score_item(item, query, mnemonic) {
+            matches.push(SearchMatch {
+                item: item.clone(),
+                path: path.clone(),
+                score,
+            });
+        }
+    }
+}
+
+/// Returns at most max_results MenuItems matching the query, best first. MenuItems with the same score keep their order in the Menu.
+/// mnemonic is Config.mnemonic, which removes the mnemonic marker from labels.
+pub(crate) fn search_items(items: &[MenuItem], query: &str, mnemonic: bool, max_results: usize) -> Vec<SearchMatch> {
+    if query.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut matches = Vec::new();
+    collect_matches(items, query, mnemonic, &mut Vec::new(), &mut matches);
+    matches.sort_by_key(|m| std::cmp::Reverse(m.score));
+    matches.truncate(max_results);
+    matches
+}
+
+impl Menu {
+    /// Finds the MenuItems of Menu and its submenus matching the query with fuzzy matching on labels and keywords, best first.
+    /// Hidden, disabled and submenu items are not listed.
+    pub fn search(&self, query: &str) -> Vec<SearchMatch> {
+        search_items(&self.items(), query, self.config().mnemonic, usize::MAX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(id: &str, label: &str) -> MenuItem {
+        MenuItem::new_text_item(id, label, None, false, None)
+    }
+
+    fn ids(matches: &[SearchMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn fuzzy_score_matches_case_insensitive_subsequence() {
+        assert!(fuzzy_score("opn", "Open").is_some());
+        assert!(fuzzy_score("OPEN", "open").is_some());
+        assert_eq!(fuzzy_score("nope", "Open"), None);
+        assert_eq!(fuzzy_score("oo", "Open"), None);
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts_and_consecutive_characters() {
+        assert!(fuzzy_score("s", "Save") > fuzzy_score("s", "Close"));
+        assert!(fuzzy_score("av", "Save") > fuzzy_score("av", "Salvage"));
+        assert!(fuzzy_score("sav", "Save") > fuzzy_score("sav", "Sort by value"));
+    }
+
+    #[test]
+    fn search_ranks_best_first_and_keeps_menu_order_for_ties() {
+        let items = vec![text("close", "Close"), text("save", "Save"), text("save_as", "Save As"), text("show_all", "Show all")];
+
+        let matches = search_items(&items, "sa", false, usize::MAX);
+
+        /* "Show all" matches both characters at word starts, "Save" and "Save As" tie */
+        assert_eq!(ids(&matches), ["show_all", "save", "save_as"]);
+        assert!(matches[0].score > matches[1].score);
+        assert_eq!(matches[1].score, matches[2].score);
+    }
+
+    #[test]
+    fn search_matches_each_word_in_label_or_keywords() {
+        let mut pdf = text("pdf", "PDF");
+        pdf.keywords = vec!["export".to_string(), "print".to_string()];
+        let items = vec![pdf, text("png", "PNG")];
+
+        assert_eq!(ids(&search_items(&items, "export", false, usize::MAX)), ["pdf"]);
+        assert_eq!(ids(&search_items(&items, "exp pdf", false, usize::MAX)), ["pdf"]);
+        assert!(search_items(&items, "exp png", false, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn search_lists_submenu_items_with_path() {
+        let mut image = MenuItem::new_submenu_item("image", "Image", false, None);
+        image.add_menu_item(text("png", "PNG"));
+        let mut export = MenuItem::new_submenu_item("export", "Export", false, None);
+        export.add_menu_item(image);
+        let mut hidden = text("hidden_png", "PNG hidden");
+        hidden.visible = false;
+        let mut disabled = text("disabled_png", "PNG disabled");
+        disabled.disabled = true;
+        let items = vec![export, hidden, disabled, MenuItem::new_separator()];
+
+        let matches = search_items(&items, "png", false, usize::MAX);
+
+        assert_eq!(ids(&matches), ["png"]);
+        assert_eq!(matches[0].path, ["Export", "Image"]);
+        assert_eq!(matches[0].path_text(), "Export › Image");
+        assert!(search_items(&items, "export", false, usize::MAX).is_empty());
+    }
+
+    #[test]
+    fn search_truncates_to_max_results() {
+        let items = vec![text("a", "Note 1"), text("b", "Note 2"), text("c", "Note 3")];
+
+        assert_eq!(ids(&search_items(&items, "note", false, 2)), ["a", "b"]);
+        assert!(search_items(&items, "note", false, 0).is_empty());
+    }
+
+    #[test]
+    fn search_returns_nothing_for_empty_query() {
+        let items = vec![text("open", "Open")];
+
+        assert!(search_items(&items, "", false, usize::MAX).is_empty());
+        assert!(search_items(&items, "   ", false, usize::MAX).is_empty());
+    }
+}
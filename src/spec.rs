@@ -0,0 +1,596 @@
+//! Declarative menu definitions.
+//!
+//! A [`MenuSpec`] is a tree of items which can be written in JSON or TOML and turned into a Menu
+//! by [`MenuBuilder::from_spec`], or by Menu::from_json()/Menu::from_toml() with "json"/"toml" feature.
+//!
+//! ```json
+//! {
+//!   "theme": "Dark",
+//!   "items": [
+//!     { "id": "open", "label": "Open", "accelerator": "Ctrl+O", "icon": "icons/open.png" },
+//!     { "id": "wrap", "type": "checkbox", "label": "Word wrap", "checked": true },
+//!     { "type": "separator" },
+//!     { "id": "small", "type": "radio", "label": "Small", "group": "size", "checked": true },
+//!     { "id": "large", "type": "radio", "label": "Large", "group": "size" },
+//!     { "id": "export", "type": "submenu", "label": "Export", "items": [
+//!       { "id": "pdf", "label": "PDF", "icon": { "path": "icons/pdf.svg", "width": 20, "height": 20 } }
+//!     ]}
+//!   ]
+//! }
+//! ```
+//!
+//! Item fields:
+//! - `type`: "text"(default), "checkbox", "radio", "submenu", "separator" or "header"
+//! - `id`: Required except for separators and headers. Must be unique in the Menu.
+//! - `label`
+//! - `accelerator`: Single key like "Ctrl+O" or chord like "Ctrl+K Ctrl+C"
+//! - `icon`: Path to an image file, or a table of `path`, `width` and `height`
+//! - `checked`: Only for checkbox and radio
+//! - `mixed`: Mixed check state. Only for checkbox and not with `checked`.
+//! - `disabled`, `visible`(default true)
+//! - `group`: Radio group name. Required for radio.
+//! - `keywords`: Words matched by search besides the label
+//! - `tooltip`: Text shown on hover. Not for separator.
+//! - `description`: Second line under the label. Not for separator.
+//! - `items`: Children. Only for submenu.
+//!
+//! A header is a section heading and accepts only `id`, `label` and `visible`.
+use crate::{
+    config::{Config, Theme},
+    validation::submenu_items,
+    CheckState, Error, Menu, MenuBuilder, MenuIcon, MenuIconKind, MenuItem, MenuItemType,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    path::PathBuf,
+};
+
+/// Icon size used when only the path is specified.
+pub const DEFAULT_SPEC_ICON_SIZE: u32 = 16;
+
+/// Declarative definition of a Menu.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MenuSpec {
+    /// Menu configuration. Config::default() is used when omitted.
+    pub config: Option<Config>,
+    /// Theme overriding the one in config.
+    pub theme: Option<Theme>,
+    pub items: Vec<MenuItemSpec>,
+}
+
+/// Declarative definition of a MenuItem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct MenuItemSpec {
+    #[serde(rename = "type", default = "default_item_type")]
+    pub item_type: MenuItemType,
+    #[serde(default)]
+    pub id: String,
+    #[serde(default)]
+    pub label: String,
+    #[serde(default)]
+    pub accelerator: Option<String>,
+    #[serde(default)]
+    pub icon: Option<IconSpec>,
+    #[serde(default)]
+    pub checked: bool,
+    /// Mixed check state of checkbox.
+    #[serde(default)]
+    pub mixed: bool,
+    #[serde(default)]
+    pub disabled: bool,
+    #[serde(default = "default_visible")]
+    pub visible: bool,
+    /// Radio group name.
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Words matched by search besides the label.
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// Text shown on hover.
+    #[serde(default)]
+    pub tooltip: Option<String>,
+    /// Second line under the label.
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub items: Vec<MenuItemSpec>,
+}
+
+fn default_item_type() -> MenuItemType {
+    MenuItemType::Text
+}
+
+fn default_visible() -> bool {
+    true
+}
+
+/// Icon of a MenuItem. Either a path or a path with size.
+/// Icons created from data or SVG are kept as they are in the model of a live Menu.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum IconSpec {
+    Path(PathBuf),
+    Sized {
+        path: PathBuf,
+        width: u32,
+        height: u32,
+    },
+    Embedded(MenuIcon),
+}
+
+impl IconSpec {
+    fn to_menu_icon(&self) -> MenuIcon {
+        match self {
+            IconSpec::Path(path) => MenuIcon::new(path, DEFAULT_SPEC_ICON_SIZE, DEFAULT_SPEC_ICON_SIZE),
+            IconSpec::Sized {
+                path,
+                width,
+                height,
+            } => MenuIcon::new(path, *width, *height),
+            IconSpec::Embedded(icon) => icon.clone(),
+        }
+    }
+
+    fn from_menu_icon(icon: &MenuIcon) -> Self {
+        match &icon.icon {
+            MenuIconKind::Path(path_icon) => IconSpec::Sized {
+                path: path_icon.path.clone(),
+                width: path_icon.width,
+                height: path_icon.height,
+            },
+            _ => IconSpec::Embedded(icon.clone()),
+        }
+    }
+}
+
+/// Error from loading a MenuSpec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpecError {
+    /// Spec text is not valid JSON/TOML or does not match the spec format.
+    Parse(String),
+    /// Spec is well-formed but has an invalid item. path is like `items[2].items[0]`.
+    Invalid {
+        path: String,
+        message: String,
+    },
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpecError::Parse(message) => write!(f, "failed to parse menu spec: {}", message),
+            SpecError::Invalid {
+                path,
+                message,
+            } => write!(f, "invalid menu spec at {}: {}", path, message),
+        }
+    }
+}
+
+impl std::error::Error for SpecError {}
+
+impl MenuSpec {
+    /// Parses a MenuSpec from JSON.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &str) -> Result<Self, SpecError> {
+        serde_json::from_str(json).map_err(|e| SpecError::Parse(e.to_string()))
+    }
+
+    /// Parses a MenuSpec from TOML.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, SpecError> {
+        toml::from_str(toml).map_err(|e| SpecError::Parse(e.to_string()))
+    }
+
+    /// Checks that every item is consistent with its type and ids are unique.
+    pub fn validate(&self) -> Result<(), SpecError> {
+        let mut ids = HashSet::new();
+        validate_items(&self.items, "items", &mut ids)
+    }
+
+    pub(crate) fn to_config(&self) -> Config {
+        let config = self.config.clone().unwrap_or_default();
+        match self.theme {
+            Some(theme) => Config {
+                theme,
+                ..config
+            },
+            None => config,
+        }
+    }
+}
+
+fn validate_items<'a>(items: &'a [MenuItemSpec], parent_path: &str, ids: &mut HashSet<&'a str>) -> Result<(), SpecError> {
+    let mut checked_groups: HashMap<&str, &str> = HashMap::new();
+
+    for (index, item) in items.iter().enumerate() {
+        let path = format!("{}[{}]", parent_path, index);
+        let invalid = |message: String| SpecError::Invalid {
+            path: path.clone(),
+            message,
+        };
+
+        if !matches!(item.item_type, MenuItemType::Separator | MenuItemType::Header) && item.id.is_empty() {
+            return Err(invalid("id is required".to_string()));
+        }
+
+        if !item.id.is_empty() && !ids.insert(&item.id) {
+            return Err(invalid(format!("duplicate id \"{}\"", item.id)));
+        }
+
+        if item.checked && !matches!(item.item_type, MenuItemType::Checkbox | MenuItemType::Radio) {
+            return Err(invalid("checked is only for checkbox and radio".to_string()));
+        }
+
+        if item.mixed && item.item_type != MenuItemType::Checkbox {
+            return Err(invalid("mixed is only for checkbox".to_string()));
+        }
+
+        if item.mixed && item.checked {
+            return Err(invalid("checked and mixed cannot be set together".to_string()));
+        }
+
+        if item.accelerator.is_some() && matches!(item.item_type, MenuItemType::Submenu | MenuItemType::Separator) {
+            return Err(invalid("accelerator is not allowed for submenu and separator".to_string()));
+        }
+
+        if !item.keywords.is_empty() && matches!(item.item_type, MenuItemType::Submenu | MenuItemType::Separator) {
+            return Err(invalid("keywords are not allowed for submenu and separator".to_string()));
+        }
+
+        if item.item_type == MenuItemType::Separator && item.icon.is_some() {
+            return Err(invalid("icon is not allowed for separator".to_string()));
+        }
+
+        if item.item_type == MenuItemType::Separator && item.tooltip.is_some() {
+            return Err(invalid("tooltip is not allowed for separator".to_string()));
+        }
+
+        if item.item_type == MenuItemType::Separator && item.description.is_some() {
+            return Err(invalid("description is not allowed for separator".to_string()));
+        }
+
+        if item.item_type == MenuItemType::Header
+            && (item.accelerator.is_some() || item.icon.is_some() || !item.keywords.is_empty() || item.tooltip.is_some() || item.description.is_some() || item.disabled)
+        {
+            return Err(invalid("header accepts only id, label and visible".to_string()));
+        }
+
+        match (item.item_type, item.group.as_deref()) {
+            (MenuItemType::Radio, None) | (MenuItemType::Radio, Some("")) => return Err(invalid("group is required for radio".to_string())),
+            (MenuItemType::Radio, Some(group)) if item.checked => {
+                if let Some(checked_id) = checked_groups.insert(group, &item.id) {
+                    return Err(invalid(format!("\"{}\" is already checked in group \"{}\"", checked_id, group)));
+                }
+            }
+            (MenuItemType::Radio, Some(_)) => {}
+            (_, Some(_)) => return Err(invalid("group is only for radio".to_string())),
+            _ => {}
+        }
+
+        if item.item_type == MenuItemType::Submenu {
+            validate_items(&item.items, &format!("{}.items", path), ids)?;
+        } else if !item.items.is_empty() {
+            return Err(invalid("items are only for submenu".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+impl MenuItemSpec {
+    /// Creates the MenuItem described by this spec.
+    pub fn to_menu_item(&self) -> MenuItem {
+        let accelerator = self.accelerator.as_deref();
+        let icon = self.icon.as_ref().map(IconSpec::to_menu_icon);
+
+        let mut item = match self.item_type {
+            MenuItemType::Text => MenuItem::new_text_item(&self.id, &self.label, accelerator, self.disabled, icon),
+            MenuItemType::Checkbox => MenuItem::new_check_item(&self.id, &self.label, accelerator, self.checked, self.disabled, icon),
+            MenuItemType::Radio => MenuItem::new_radio_item(&self.id, &self.label, self.group.as_deref().unwrap_or_default(), accelerator, self.checked, self.disabled, icon),
+            MenuItemType::Submenu => {
+                let mut item = MenuItem::new_submenu_item(&self.id, &self.label, self.disabled, icon);
+                for child in &self.items {
+                    item.add_menu_item(child.to_menu_item());
+                }
+                item
+            }
+            MenuItemType::Separator => {
+                if self.id.is_empty() {
+                    MenuItem::new_separator()
+                } else {
+                    MenuItem::new_separator_with_id(&self.id)
+                }
+            }
+            MenuItemType::Header => MenuItem::new_header_with_id(&self.id, &self.label),
+        };
+
+        item.visible = self.visible;
+        if self.mixed {
+            item.check_state = CheckState::Mixed;
+        }
+        item.keywords.clone_from(&self.keywords);
+        item.tooltip.clone_from(&self.tooltip);
+        item.description.clone_from(&self.description);
+        item
+    }
+
+    /// Creates the spec describing the current state of the MenuItem including its submenu.
+    pub fn from_menu_item(item: &MenuItem) -> Self {
+        let items = submenu_items(item);
+
+        Self {
+            item_type: item.menu_item_type,
+            id: item.id.clone(),
+            label: item.label.clone(),
+            accelerator: if item.accelerator.is_empty() {
+                None
+            } else {
+                Some(item.accelerator.clone())
+            },
+            icon: item.icon.as_ref().map(IconSpec::from_menu_icon),
+            checked: item.check_state.is_checked(),
+            mixed: item.check_state == CheckState::Mixed,
+            disabled: item.disabled,
+            visible: item.visible,
+            group: if item.menu_item_type == MenuItemType::Radio {
+                Some(item.name.clone())
+            } else {
+                None
+            },
+            keywords: item.keywords.clone(),
+            tooltip: item.tooltip.clone(),
+            description: item.description.clone(),
+            items: items.iter().map(MenuItemSpec::from_menu_item).collect(),
+        }
+    }
+}
+
+impl MenuBuilder {
+    /// Creates a MenuBuilder for the specified window handle with the items in the MenuSpec.
+    /// More items can be added before build.
+    pub fn from_spec(window_handle: isize, spec: &MenuSpec) -> Result<Self, Error> {
+        spec.validate()?;
+
+        let mut builder = MenuBuilder::new_from_config(window_handle, spec.to_config());
+        for item in &spec.items {
+            builder.append(item.to_menu_item());
+        }
+
+        Ok(builder)
+    }
+}
+
+impl Menu {
+    /// Returns the platform-neutral tree of the current items, free of any handle.
+    pub fn to_model(&self) -> MenuSpec {
+        MenuSpec {
+            config: Some(self.config()),
+            theme: None,
+            items: self.items().iter().map(MenuItemSpec::from_menu_item).collect(),
+        }
+    }
+
+    /// Rebuilds a Menu for the specified window handle from the model returned by [`Menu::to_model`].
+    /// Unlike [`MenuBuilder::from_spec`], the spec rules such as required ids are not checked so that any Menu can be reproduced.
+    pub fn from_model(window_handle: isize, model: &MenuSpec) -> Result<Self, Error> {
+        let mut builder = MenuBuilder::new_from_config(window_handle, model.to_config());
+        for item in &model.items {
+            builder.append(item.to_menu_item());
+        }
+        builder.build()
+    }
+
+    /// Builds a Menu for the specified window handle from a JSON MenuSpec.
+    #[cfg(feature = "json")]
+    pub fn from_json(window_handle: isize, json: &str) -> Result<Self, Error> {
+        let spec = MenuSpec::from_json(json)?;
+        MenuBuilder::from_spec(window_handle, &spec)?.build()
+    }
+
+    /// Builds a Menu for the specified window handle from a TOML MenuSpec.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(window_handle: isize, toml: &str) -> Result<Self, Error> {
+        let spec = MenuSpec::from_toml(toml)?;
+        MenuBuilder::from_spec(window_handle, &spec)?.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(item_type: MenuItemType, id: &str) -> MenuItemSpec {
+        MenuItemSpec {
+            item_type,
+            id: id.to_string(),
+            label: id.to_string(),
+            accelerator: None,
+            icon: None,
+            checked: false,
+            mixed: false,
+            disabled: false,
+            visible: true,
+            group: None,
+            keywords: Vec::new(),
+            tooltip: None,
+            description: None,
+            items: Vec::new(),
+        }
+    }
+
+    fn radio(id: &str, group: &str, checked: bool) -> MenuItemSpec {
+        MenuItemSpec {
+            group: Some(group.to_string()),
+            checked,
+            ..item(MenuItemType::Radio, id)
+        }
+    }
+
+    fn validate(items: Vec<MenuItemSpec>) -> Result<(), SpecError> {
+        MenuSpec {
+            items,
+            ..Default::default()
+        }
+        .validate()
+    }
+
+    fn invalid(path: &str, message: &str) -> Result<(), SpecError> {
+        Err(SpecError::Invalid {
+            path: path.to_string(),
+            message: message.to_string(),
+        })
+    }
+
+    #[test]
+    fn validate_accepts_valid_items() {
+        let mut submenu = item(MenuItemType::Submenu, "more");
+        submenu.items = vec![item(MenuItemType::Text, "nested"), item(MenuItemType::Separator, "")];
+        let items = vec![
+            item(MenuItemType::Header, ""),
+            MenuItemSpec {
+                mixed: true,
+                ..item(MenuItemType::Checkbox, "bold")
+            },
+            radio("small", "size", true),
+            radio("large", "size", false),
+            submenu,
+        ];
+
+        assert_eq!(validate(items), Ok(()));
+    }
+
+    #[test]
+    fn validate_reports_item_path() {
+        let mut submenu = item(MenuItemType::Submenu, "more");
+        submenu.items = vec![item(MenuItemType::Text, "nested"), item(MenuItemType::Text, "open")];
+
+        assert_eq!(validate(vec![item(MenuItemType::Text, "open"), submenu]), invalid("items[1].items[1]", "duplicate id \"open\""));
+        assert_eq!(validate(vec![item(MenuItemType::Separator, ""), item(MenuItemType::Text, "")]), invalid("items[1]", "id is required"));
+    }
+
+    #[test]
+    fn validate_rejects_inconsistent_items() {
+        let table = [
+            (
+                MenuItemSpec {
+                    checked: true,
+                    ..item(MenuItemType::Text, "a")
+                },
+                "checked is only for checkbox and radio",
+            ),
+            (
+                MenuItemSpec {
+                    mixed: true,
+                    ..radio("a", "size", false)
+                },
+                "mixed is only for checkbox",
+            ),
+            (
+                MenuItemSpec {
+                    mixed: true,
+                    checked: true,
+                    ..item(MenuItemType::Checkbox, "a")
+                },
+                "checked and mixed cannot be set together",
+            ),
+            (
+                MenuItemSpec {
+                    accelerator: Some("Ctrl+S".to_string()),
+                    ..item(MenuItemType::Submenu, "a")
+                },
+                "accelerator is not allowed for submenu and separator",
+            ),
+            (
+                MenuItemSpec {
+                    tooltip: Some("tip".to_string()),
+                    ..item(MenuItemType::Separator, "")
+                },
+                "tooltip is not allowed for separator",
+            ),
+            (
+                MenuItemSpec {
+                    disabled: true,
+                    ..item(MenuItemType::Header, "")
+                },
+                "header accepts only id, label and visible",
+            ),
+            (item(MenuItemType::Radio, "a"), "group is required for radio"),
+            (
+                MenuItemSpec {
+                    group: Some("size".to_string()),
+                    ..item(MenuItemType::Text, "a")
+                },
+                "group is only for radio",
+            ),
+            (
+                MenuItemSpec {
+                    items: vec![item(MenuItemType::Text, "b")],
+                    ..item(MenuItemType::Text, "a")
+                },
+                "items are only for submenu",
+            ),
+        ];
+
+        for (spec, message) in table {
+            assert_eq!(validate(vec![spec]), invalid("items[0]", message));
+        }
+    }
+
+    #[test]
+    fn validate_rejects_second_checked_radio_in_group() {
+        let items = vec![radio("small", "size", true), radio("left", "align", true), radio("large", "size", true)];
+
+        assert_eq!(validate(items), invalid("items[2]", "\"small\" is already checked in group \"size\""));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn from_json_rejects_unknown_fields() {
+        assert!(matches!(MenuSpec::from_json(r#"{ "items": [{ "id": "open", "lable": "Open" }] }"#), Err(SpecError::Parse(message)) if message.contains("unknown field `lable`")));
+        assert!(matches!(MenuSpec::from_json(r#"{ "itmes": [] }"#), Err(SpecError::Parse(message)) if message.contains("unknown field `itmes`")));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn menu_from_json_maps_errors_to_invalid_spec() {
+        assert!(matches!(Menu::from_json(0, "{ \"items\": ["), Err(Error::InvalidSpec(SpecError::Parse(_)))));
+        assert!(matches!(
+            Menu::from_json(0, r#"{ "items": [{ "type": "radio", "id": "small" }] }"#),
+            Err(Error::InvalidSpec(SpecError::Invalid { path, .. })) if path == "items[0]"
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn from_toml_rejects_unknown_fields() {
+        let toml = r#"
+            [[items]]
+            id = "open"
+            lable = "Open"
+        "#;
+        assert!(matches!(MenuSpec::from_toml(toml), Err(SpecError::Parse(message)) if message.contains("unknown field `lable`")));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn menu_from_toml_maps_errors_to_invalid_spec() {
+        assert!(matches!(Menu::from_toml(0, "[[items]"), Err(Error::InvalidSpec(SpecError::Parse(_)))));
+
+        let toml = r#"
+            [[items]]
+            type = "checkbox"
+            id = "wrap"
+            checked = true
+            mixed = true
+        "#;
+        assert!(matches!(
+            Menu::from_toml(0, toml),
+            Err(Error::InvalidSpec(SpecError::Invalid { path, message })) if path == "items[0]" && message == "checked and mixed cannot be set together"
+        ));
+    }
+}
@@ -0,0 +1,198 @@
+use crate::{
+    config::{AcceleratorConflictPolicy, Config},
+    AcceleratorChord, AcceleratorConflict, Error, MenuIcon, MenuIconKind, MenuItem, MenuItemType,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+};
+
+pub(crate) fn validate_config(config: &Config) -> Result<(), Error> {
+    let size = &config.size;
+    let sizes = [
+        ("border_size", size.border_size),
+        ("vertical_padding", size.vertical_padding),
+        ("horizontal_padding", size.horizontal_padding),
+        ("item_vertical_padding", size.item_vertical_padding),
+        ("item_horizontal_padding", size.item_horizontal_padding),
+        ("separator_size", size.separator_size),
+    ];
+    if let Some((name, value)) = sizes.iter().find(|(_, value)| *value < 0) {
+        return Err(Error::InvalidConfig(format!("{} must not be negative but was {}", name, value)));
+    }
+
+    let font = &config.font;
+    if font.font_family.trim().is_empty() || font.font_family.contains(['"', ';', '{', '}']) {
+        return Err(Error::InvalidConfig(format!("font_family \"{}\" is empty or contains any of \";{{}}", font.font_family)));
+    }
+
+    for (name, value) in [
+        ("dark_font_size", font.dark_font_size),
+        ("light_font_size", font.light_font_size),
+        ("dark_description_font_size", font.dark_description_font_size),
+        ("light_description_font_size", font.light_description_font_size),
+    ] {
+        if !value.is_finite() || value <= 0.0 {
+            return Err(Error::InvalidConfig(format!("{} must be positive but was {}", name, value)));
+        }
+    }
+
+    if let Some(icon) = &config.icon {
+        if icon.horizontal_margin.is_some_and(|margin| margin < 0) {
+            return Err(Error::InvalidConfig("horizontal_margin must not be negative".to_string()));
+        }
+
+        for menu_icon in [&icon.check, &icon.arrow, &icon.mixed].into_iter().flatten() {
+            validate_icon(menu_icon)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn validate_accelerator(accelerator: &str) -> Result<(), Error> {
+    accelerator.parse::<AcceleratorChord>().map(|_| ())
+}
+
+pub(crate) fn validate_icon(icon: &MenuIcon) -> Result<(), Error> {
+    match &icon.icon {
+        MenuIconKind::Path(path_icon) => {
+            /* Path is embedded in CSS url() on Linux */
+            if path_icon.path.to_string_lossy().contains('"') {
+                return Err(Error::IconLoad {
+                    path: path_icon.path.clone(),
+                    source: io::Error::new(io::ErrorKind::InvalidInput, "path contains '\"'"),
+                });
+            }
+
+            fs::metadata(&path_icon.path).map_err(|source| Error::IconLoad {
+                path: path_icon.path.clone(),
+                source,
+            })?;
+        }
+        MenuIconKind::Data(data_icon) => {
+            let pixels = data_icon.width as usize * data_icon.height as usize;
+            if pixels == 0 || (data_icon.data.len() != pixels * 3 && data_icon.data.len() != pixels * 4) {
+                return Err(Error::InvalidIcon(format!("{} bytes do not match {}x{} RGB/RGBA", data_icon.data.len(), data_icon.width, data_icon.height)));
+            }
+        }
+        MenuIconKind::Svg(svg_icon) => {
+            if svg_icon.width == 0 || svg_icon.height == 0 {
+                return Err(Error::InvalidSvg(format!("size {}x{} is empty", svg_icon.width, svg_icon.height)));
+            }
+
+            if !svg_icon.data.contains("<svg") {
+                return Err(Error::InvalidSvg("no svg element".to_string()));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Validates MenuItems and their submenus. ids collects the ids already used in the Menu.
+pub(crate) fn validate_items(items: &[MenuItem], ids: &mut HashSet<String>) -> Result<(), Error> {
+    for item in items {
+        if !item.id.is_empty() && !ids.insert(item.id.clone()) {
+            return Err(Error::DuplicateId(item.id.clone()));
+        }
+
+        if !item.accelerator.is_empty() {
+            validate_accelerator(&item.accelerator)?;
+        }
+
+        if let Some(icon) = &item.icon {
+            validate_icon(icon)?;
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            validate_items(&submenu_items(item), ids)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks that no MenuItems in the Menu and its submenus share the same accelerator.
+/// A chord starting with another accelerator like "Ctrl+K Ctrl+C" and "Ctrl+K" is a conflict as well.
+/// accelerators collects the accelerators already used in the Menu with the id of their MenuItem.
+/// Conflicts reported as warnings are added to warnings.
+pub(crate) fn validate_accelerator_conflicts(
+    items: &[MenuItem],
+    accelerators: &mut HashMap<AcceleratorChord, String>,
+    policy: AcceleratorConflictPolicy,
+    warnings: &mut Vec<AcceleratorConflict>,
+) -> Result<(), Error> {
+    for item in items {
+        if item.menu_item_type == MenuItemType::Submenu {
+            validate_accelerator_conflicts(&submenu_items(item), accelerators, policy, warnings)?;
+        } else if let Ok(chord) = item.accelerator.parse::<AcceleratorChord>() {
+            match accelerators.iter().find(|(other, _)| chord.starts_with(other) || other.starts_with(&chord)) {
+                Some((_, other_id)) => report_accelerator_conflict(
+                    AcceleratorConflict {
+                        accelerator: chord.to_string(),
+                        id: item.id.clone(),
+                        other_id: other_id.clone(),
+                    },
+                    policy,
+                    warnings,
+                )?,
+                None => {
+                    accelerators.insert(chord, item.id.clone());
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks the accelerators of the MenuItem to be added against the ones already used in the Menu.
+/// Returns the accelerators of the new MenuItem.
+pub(crate) fn validate_new_accelerators(
+    menu_items: &[MenuItem],
+    item: &MenuItem,
+    policy: AcceleratorConflictPolicy,
+    warnings: &mut Vec<AcceleratorConflict>,
+) -> Result<HashMap<AcceleratorChord, String>, Error> {
+    let mut accelerators = HashMap::new();
+    validate_accelerator_conflicts(menu_items, &mut accelerators, AcceleratorConflictPolicy::Allow, &mut Vec::new())?;
+    validate_accelerator_conflicts(std::slice::from_ref(item), &mut accelerators, policy, warnings)?;
+
+    let mut new_accelerators = HashMap::new();
+    validate_accelerator_conflicts(std::slice::from_ref(item), &mut new_accelerators, AcceleratorConflictPolicy::Allow, &mut Vec::new())?;
+    Ok(new_accelerators)
+}
+
+/// Returns the conflict as error or adds it to warnings according to the policy.
+pub(crate) fn report_accelerator_conflict(conflict: AcceleratorConflict, policy: AcceleratorConflictPolicy, warnings: &mut Vec<AcceleratorConflict>) -> Result<(), Error> {
+    match policy {
+        AcceleratorConflictPolicy::Error => Err(conflict.into()),
+        AcceleratorConflictPolicy::Warn => {
+            warnings.push(conflict);
+            Ok(())
+        }
+        AcceleratorConflictPolicy::Allow => Ok(()),
+    }
+}
+
+/// Collects the ids used in the MenuItems and their submenus.
+pub(crate) fn collect_ids(items: &[MenuItem], ids: &mut HashSet<String>) {
+    for item in items {
+        if !item.id.is_empty() {
+            ids.insert(item.id.clone());
+        }
+
+        if item.menu_item_type == MenuItemType::Submenu {
+            collect_ids(&submenu_items(item), ids);
+        }
+    }
+}
+
+pub(crate) fn submenu_items(item: &MenuItem) -> Vec<MenuItem> {
+    match (&item.submenu, &item.items) {
+        (Some(submenu), _) => submenu.items(),
+        (None, Some(items)) => items.clone(),
+        (None, None) => Vec::new(),
+    }
+}